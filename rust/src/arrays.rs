@@ -1,4 +1,4 @@
-/// Utilities for manipulating arrays, used in the wavelet transforms.
+//! Utilities for manipulating arrays, used in the wavelet transforms.
 
 /// Permute the elements in the slice such that even-numbered elements
 /// are moved to the front, and odd-numbered elements are moved to the back,
@@ -7,64 +7,108 @@
 /// into [0,2,4,1,3,5].
 /// This tranformation is performed _in place_, so the input slice will not
 /// have elements in the same location when this function call is done.
-pub fn partition_evens<E>(d: &mut [E])
-where
-    E: Default,
-{
-    use std::mem;
-    if d.len() < 2 {
+pub fn partition_evens<E>(d: &mut [E]) {
+    // This works by divide and conquer, the mirror image of [`interleave`]: we split the slice
+    // into a front part (of even length) and a back part, recursively partition each of them to
+    // get `[E1 | O1 | E2 | O2]`, and then rotate the middle to get `[E1 | E2 | O1 | O2]`. This
+    // takes `N lg(N)` steps, but requires no additional memory.
+    if d.len() < 3 {
         //nothing to do
         return;
     }
 
-    let mut p = 1;
-    let mut n = 0;
-    let mid = if d.len() % 2 == 0 {
-        d.len() / 2
-    } else {
-        d.len() / 2 + 1
-    };
-
-    while p < mid {
-        let sn = n;
-        let mut t = mem::replace(&mut d[p], E::default());
+    let front = (d.len() / 2 + 1) & !1;
+    let (left, right) = d.split_at_mut(front);
+    partition_evens(left);
+    partition_evens(right);
+
+    let k = front / 2;
+    let right_evens = (d.len() - front).div_ceil(2);
+    d[k..front + right_evens].rotate_left(k);
+}
+
+/// Perform the inverse of [`partition_evens`].
+///
+/// The front half of the slice (rounded up) is moved to the even-numbered positions, and the back
+/// half is moved to the odd-numbered positions, so [0,2,4,1,3,5] becomes [0,1,2,3,4,5].
+///
+/// This works in place by divide and conquer: if the slice is `[A | D]`, then we split
+/// each of `A` and `D` into a front and back part `[A1 | A2 | D1 | D2]`, rotate the middle so we have
+/// `[A1 | D1 | A2 | D2]`, and then recursively interleave `[A1 | D1]` and `[A2 | D2]`. This
+/// takes `N lg(N)` steps, but requires no additional memory.
+pub fn interleave<E>(d: &mut [E]) {
+    let evens = d.len().div_ceil(2);
+    let odds = d.len() - evens;
+    let k = odds / 2;
+    if k == 0 {
+        if evens == 2 {
+            // [a0, a1, d0]
+            d[1..].rotate_right(1);
+        }
+        return;
+    }
+
+    d[k..evens + k].rotate_right(k);
+    let (front, back) = d.split_at_mut(2 * k);
+    interleave(front);
+    interleave(back);
+}
+
+/// Permute the elements in the slice so that the element at `source(i)` is moved to `i`, where
+/// `source` is a permutation of the positions.
+///
+/// This follows each cycle of the permutation, swapping the elements along it into place, so it
+/// takes `N` steps, and only needs a bit per element to remember which have been moved.
+pub fn gather<E>(d: &mut [E], source: impl Fn(usize) -> usize) {
+    let mut moved = Moved::new(d.len());
+    for start in 0..d.len() {
+        if moved.test_and_set(start) {
+            continue;
+        }
+        let mut pos = start;
         loop {
-            //compute destination
-            let dp = if p % 2 == 0 { n } else { mid + n };
-            let t2 = mem::replace(&mut d[dp], E::default());
-            d[dp] = t;
-            t = t2;
-            p = dp;
-            n = if dp % 2 == 0 { dp / 2 } else { (dp - 1) / 2 };
-
-            if n == sn {
+            let from = source(pos);
+            if from == start {
                 break;
             }
+            d.swap(pos, from);
+            moved.test_and_set(from);
+            pos = from;
         }
-        n += 1;
-        p = 2 * n + 1;
     }
 }
 
-pub fn pad<T>(d: &[T]) -> Vec<f64>
-where
-    T: Into<f64> + Copy,
-{
-    if d.len() & (d.len() - 1) != 0 {
-        //the size isn't a power of two, so pad it to the nearest
-        let mut p = 0;
-        let mut s = 1;
-        while s < d.len() {
-            s <<= 1;
-            p += 1;
+/// Perform the inverse of [`gather`], moving the element at `i` to `target(i)`.
+pub fn scatter<E>(d: &mut [E], target: impl Fn(usize) -> usize) {
+    let mut moved = Moved::new(d.len());
+    for start in 0..d.len() {
+        if moved.test_and_set(start) {
+            continue;
+        }
+        // the element at `start` is always the next one to be put in place
+        let mut to = target(start);
+        while to != start {
+            d.swap(start, to);
+            moved.test_and_set(to);
+            to = target(to);
         }
+    }
+}
+
+/// A bit per element of a slice, for [`gather`] and [`scatter`].
+struct Moved(Vec<u64>);
 
-        let mut padded: Vec<f64> = Vec::with_capacity(p);
-        d.into_iter().for_each(|v| padded.push((*v).into()));
-        padded.resize(s, 0.0);
-        padded
-    } else {
-        d.into_iter().map(|v| (*v).into()).collect()
+impl Moved {
+    fn new(len: usize) -> Moved {
+        Moved(vec![0; len.div_ceil(64)])
+    }
+
+    /// Mark a position as moved, returning whether it already was.
+    fn test_and_set(&mut self, pos: usize) -> bool {
+        let (word, bit) = (pos / 64, 1 << (pos % 64));
+        let was = self.0[word] & bit != 0;
+        self.0[word] |= bit;
+        was
     }
 }
 
@@ -107,6 +151,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::manual_is_multiple_of)]
     fn partition_several() {
         for size in 0..10 {
             let mut data: Vec<usize> = Vec::new();
@@ -135,4 +180,48 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn interleave_works() {
+        for size in 0..20 {
+            let expected: Vec<usize> = (0..size).collect();
+            let mut data: Vec<usize> = (0..size).step_by(2).chain((1..size).step_by(2)).collect();
+
+            super::interleave(&mut data[..]);
+
+            assert_eq!(expected, data, "Incorrect permutation for size {}!", size);
+        }
+    }
+
+    #[test]
+    fn partition_preserves_order() {
+        for size in 0..40 {
+            let mut data: Vec<usize> = (0..size).collect();
+            let expected: Vec<usize> = (0..size).step_by(2).chain((1..size).step_by(2)).collect();
+
+            super::partition_evens(&mut data[..]);
+
+            assert_eq!(expected, data, "Incorrect permutation for size {}!", size);
+        }
+    }
+
+    #[test]
+    fn gather_and_scatter_invert_each_other() {
+        for size in 0..40 {
+            // a permutation with cycles of several lengths
+            if size % 7 == 0 {
+                continue;
+            }
+            let source = |i: usize| (i * 7 + 3) % size;
+            let original: Vec<usize> = (0..size).collect();
+            let mut data = original.clone();
+
+            super::gather(&mut data[..], source);
+            let expected: Vec<usize> = (0..size).map(source).collect();
+            assert_eq!(expected, data, "Incorrect gather for size {}!", size);
+
+            super::scatter(&mut data[..], source);
+            assert_eq!(original, data, "Incorrect scatter for size {}!", size);
+        }
+    }
 }
@@ -0,0 +1,708 @@
+// The biorthogonal wavelets of Cohen, Daubechies and Feauveau.
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::filter::{Filter, FilterBank};
+use crate::wavelet::{BiorthogonalDecomposition, BiorthogonalWavelet};
+
+// Scaling coefficients for the biorthogonal wavelets. As `matrix.md` points out, (other than Haar)
+// there are no symmetric orthogonal wavelets with compact support. But if we give up on using the
+// same filters to analyse and to reconstruct the data, we can have both symmetry and compact
+// support.
+//
+// The wavelet `biorNr.Nd` has a reconstruction (primal) scaling filter of the form
+// `cos^Nr(w/2) q(sin^2(w/2))`, and a decomposition (dual) scaling filter of the form
+// `cos^Nd(w/2) ~q(sin^2(w/2))`, where `q ~q` is the Daubechies polynomial with (Nr+Nd)/2 terms.
+// For `bior1.x`, `bior2.x` and `bior3.x`, `q = 1`, so the primal scaling function is a B-spline.
+// For `bior4.4`, `bior5.5` and `bior6.8`, the roots of the Daubechies polynomial are shared out so
+// that the two filters have similar lengths. `bior4.4` is the CDF 9/7 wavelet used by JPEG 2000.
+
+const BIOR1_1: [f64; 2] = [FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+
+const BIOR1_1_DUAL: [f64; 2] = [FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+
+const BIOR1_3: [f64; 2] = [FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+
+const BIOR1_3_DUAL: [f64; 6] = [
+    -0.08838834764831845,
+    0.08838834764831845,
+    FRAC_1_SQRT_2,
+    FRAC_1_SQRT_2,
+    0.08838834764831845,
+    -0.08838834764831845,
+];
+
+const BIOR1_5: [f64; 2] = [FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+
+const BIOR1_5_DUAL: [f64; 10] = [
+    0.016572815184059706,
+    -0.016572815184059706,
+    -0.12153397801643785,
+    0.12153397801643785,
+    FRAC_1_SQRT_2,
+    FRAC_1_SQRT_2,
+    0.12153397801643785,
+    -0.12153397801643785,
+    -0.016572815184059706,
+    0.016572815184059706,
+];
+
+const BIOR2_2: [f64; 3] = [0.3535533905932738, FRAC_1_SQRT_2, 0.3535533905932738];
+
+const BIOR2_2_DUAL: [f64; 5] = [
+    -0.1767766952966369,
+    0.3535533905932738,
+    1.0606601717798212,
+    0.3535533905932738,
+    -0.1767766952966369,
+];
+
+const BIOR2_4: [f64; 3] = [0.3535533905932738, FRAC_1_SQRT_2, 0.3535533905932738];
+
+const BIOR2_4_DUAL: [f64; 9] = [
+    0.03314563036811941,
+    -0.06629126073623882,
+    -0.1767766952966369,
+    0.4198446513295126,
+    0.9943689110435825,
+    0.4198446513295126,
+    -0.1767766952966369,
+    -0.06629126073623882,
+    0.03314563036811941,
+];
+
+const BIOR2_6: [f64; 3] = [0.3535533905932738, FRAC_1_SQRT_2, 0.3535533905932738];
+
+const BIOR2_6_DUAL: [f64; 13] = [
+    -0.006905339660024878,
+    0.013810679320049757,
+    0.04695630968816917,
+    -0.1077232986963881,
+    -0.16987135563661201,
+    0.4474660099696121,
+    0.966747552403483,
+    0.4474660099696121,
+    -0.16987135563661201,
+    -0.1077232986963881,
+    0.04695630968816917,
+    0.013810679320049757,
+    -0.006905339660024878,
+];
+
+const BIOR2_8: [f64; 3] = [0.3535533905932738, FRAC_1_SQRT_2, 0.3535533905932738];
+
+const BIOR2_8_DUAL: [f64; 17] = [
+    0.0015105430506304422,
+    -0.0030210861012608843,
+    -0.012947511862546647,
+    0.02891610982635418,
+    0.05299848189069094,
+    -0.13491307360773605,
+    -0.16382918343409023,
+    0.46257144047591653,
+    0.9516421218971786,
+    0.46257144047591653,
+    -0.16382918343409023,
+    -0.13491307360773605,
+    0.05299848189069094,
+    0.02891610982635418,
+    -0.012947511862546647,
+    -0.0030210861012608843,
+    0.0015105430506304422,
+];
+
+const BIOR3_1: [f64; 4] = [
+    0.1767766952966369,
+    0.5303300858899106,
+    0.5303300858899106,
+    0.1767766952966369,
+];
+
+const BIOR3_1_DUAL: [f64; 4] = [
+    -0.3535533905932738,
+    1.0606601717798212,
+    1.0606601717798212,
+    -0.3535533905932738,
+];
+
+const BIOR3_3: [f64; 4] = [
+    0.1767766952966369,
+    0.5303300858899106,
+    0.5303300858899106,
+    0.1767766952966369,
+];
+
+const BIOR3_3_DUAL: [f64; 8] = [
+    0.06629126073623882,
+    -0.1988737822087165,
+    -0.15467960838455727,
+    0.9943689110435825,
+    0.9943689110435825,
+    -0.15467960838455727,
+    -0.1988737822087165,
+    0.06629126073623882,
+];
+
+const BIOR3_5: [f64; 4] = [
+    0.1767766952966369,
+    0.5303300858899106,
+    0.5303300858899106,
+    0.1767766952966369,
+];
+
+const BIOR3_5_DUAL: [f64; 12] = [
+    -0.013810679320049757,
+    0.04143203796014927,
+    0.052480581416189075,
+    -0.26792717880896527,
+    -0.07181553246425873,
+    0.966747552403483,
+    0.966747552403483,
+    -0.07181553246425873,
+    -0.26792717880896527,
+    0.052480581416189075,
+    0.04143203796014927,
+    -0.013810679320049757,
+];
+
+const BIOR3_7: [f64; 4] = [
+    0.1767766952966369,
+    0.5303300858899106,
+    0.5303300858899106,
+    0.1767766952966369,
+];
+
+const BIOR3_7_DUAL: [f64; 16] = [
+    0.0030210861012608843,
+    -0.009063258303782653,
+    -0.01683176542131064,
+    0.074663985074019,
+    0.03133297870736289,
+    -0.301159125922835,
+    -0.02649924094534547,
+    0.9516421218971786,
+    0.9516421218971786,
+    -0.02649924094534547,
+    -0.301159125922835,
+    0.03133297870736289,
+    0.074663985074019,
+    -0.01683176542131064,
+    -0.009063258303782653,
+    0.0030210861012608843,
+];
+
+const BIOR3_9: [f64; 4] = [
+    0.1767766952966369,
+    0.5303300858899106,
+    0.5303300858899106,
+    0.1767766952966369,
+];
+
+const BIOR3_9_DUAL: [f64; 20] = [
+    -0.0006797443727836989,
+    0.002039233118351097,
+    0.005060319219611981,
+    -0.020618912641105536,
+    -0.014112787930175844,
+    0.09913478249423216,
+    0.012300136269419315,
+    -0.32019196836077857,
+    0.0020500227115698858,
+    0.9421257006782068,
+    0.9421257006782068,
+    0.0020500227115698858,
+    -0.32019196836077857,
+    0.012300136269419315,
+    0.09913478249423216,
+    -0.014112787930175844,
+    -0.020618912641105536,
+    0.005060319219611981,
+    0.002039233118351097,
+    -0.0006797443727836989,
+];
+
+const BIOR4_4: [f64; 7] = [
+    -0.06453888262893843,
+    -0.04068941760955844,
+    0.4180922732222122,
+    0.7884856164056644,
+    0.4180922732222122,
+    -0.04068941760955844,
+    -0.06453888262893843,
+];
+
+const BIOR4_4_DUAL: [f64; 9] = [
+    0.03782845550699546,
+    -0.02384946501938,
+    -0.1106244044184234,
+    0.37740285561265374,
+    0.8526986790094034,
+    0.37740285561265374,
+    -0.1106244044184234,
+    -0.02384946501938,
+    0.03782845550699546,
+];
+
+const BIOR5_5: [f64; 10] = [
+    0.019843544173931266,
+    0.023817598492625127,
+    -0.023257839915484257,
+    0.1455707467437649,
+    0.5411327316917105,
+    0.5411327316917105,
+    0.1455707467437649,
+    -0.023257839915484257,
+    0.023817598492625127,
+    0.019843544173931266,
+];
+
+const BIOR5_5_DUAL: [f64; 10] = [
+    0.026913418918839044,
+    -0.03230335268005385,
+    -0.24110981664787562,
+    0.054100421846547044,
+    0.899506109749091,
+    0.899506109749091,
+    0.054100421846547044,
+    -0.24110981664787562,
+    -0.03230335268005385,
+    0.026913418918839044,
+];
+
+const BIOR6_8: [f64; 11] = [
+    0.014426282505622248,
+    0.014467504896774099,
+    -0.07872200106266872,
+    -0.040367979030381904,
+    0.41784910915032025,
+    0.7589077294537632,
+    0.41784910915032025,
+    -0.040367979030381904,
+    -0.07872200106266872,
+    0.014467504896774099,
+    0.014426282505622248,
+];
+
+const BIOR6_8_DUAL: [f64; 17] = [
+    0.0019088317364850261,
+    -0.0019142861290808862,
+    -0.0169906398676071,
+    0.01193456527972673,
+    0.049732903490937654,
+    -0.07726317316721135,
+    -0.09405920349576163,
+    0.42079628460983926,
+    0.8259229974584397,
+    0.42079628460983926,
+    -0.09405920349576163,
+    -0.07726317316721135,
+    0.049732903490937654,
+    0.01193456527972673,
+    -0.0169906398676071,
+    -0.0019142861290808862,
+    0.0019088317364850261,
+];
+
+/// The biorthogonal wavelet bior1.1, with a 2-coefficient reconstruction filter and a
+/// 2-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior1_1;
+
+impl Filter for Bior1_1 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR1_1, &BIOR1_1_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior1.1 wavelet.
+pub type Bior1_1Wavelet = BiorthogonalWavelet<Bior1_1>;
+
+/// The Multi-Resolution Decomposition using the bior1.1 wavelet.
+pub type Bior1_1Decomposition = BiorthogonalDecomposition<Bior1_1>;
+
+/// The biorthogonal wavelet bior1.3, with a 2-coefficient reconstruction filter and a
+/// 6-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior1_3;
+
+impl Filter for Bior1_3 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR1_3, &BIOR1_3_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior1.3 wavelet.
+pub type Bior1_3Wavelet = BiorthogonalWavelet<Bior1_3>;
+
+/// The Multi-Resolution Decomposition using the bior1.3 wavelet.
+pub type Bior1_3Decomposition = BiorthogonalDecomposition<Bior1_3>;
+
+/// The biorthogonal wavelet bior1.5, with a 2-coefficient reconstruction filter and a
+/// 10-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior1_5;
+
+impl Filter for Bior1_5 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR1_5, &BIOR1_5_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior1.5 wavelet.
+pub type Bior1_5Wavelet = BiorthogonalWavelet<Bior1_5>;
+
+/// The Multi-Resolution Decomposition using the bior1.5 wavelet.
+pub type Bior1_5Decomposition = BiorthogonalDecomposition<Bior1_5>;
+
+/// The biorthogonal wavelet bior2.2, with a 3-coefficient reconstruction filter and a
+/// 5-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior2_2;
+
+impl Filter for Bior2_2 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR2_2, &BIOR2_2_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior2.2 wavelet.
+pub type Bior2_2Wavelet = BiorthogonalWavelet<Bior2_2>;
+
+/// The Multi-Resolution Decomposition using the bior2.2 wavelet.
+pub type Bior2_2Decomposition = BiorthogonalDecomposition<Bior2_2>;
+
+/// The biorthogonal wavelet bior2.4, with a 3-coefficient reconstruction filter and a
+/// 9-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior2_4;
+
+impl Filter for Bior2_4 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR2_4, &BIOR2_4_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior2.4 wavelet.
+pub type Bior2_4Wavelet = BiorthogonalWavelet<Bior2_4>;
+
+/// The Multi-Resolution Decomposition using the bior2.4 wavelet.
+pub type Bior2_4Decomposition = BiorthogonalDecomposition<Bior2_4>;
+
+/// The biorthogonal wavelet bior2.6, with a 3-coefficient reconstruction filter and a
+/// 13-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior2_6;
+
+impl Filter for Bior2_6 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR2_6, &BIOR2_6_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior2.6 wavelet.
+pub type Bior2_6Wavelet = BiorthogonalWavelet<Bior2_6>;
+
+/// The Multi-Resolution Decomposition using the bior2.6 wavelet.
+pub type Bior2_6Decomposition = BiorthogonalDecomposition<Bior2_6>;
+
+/// The biorthogonal wavelet bior2.8, with a 3-coefficient reconstruction filter and a
+/// 17-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior2_8;
+
+impl Filter for Bior2_8 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR2_8, &BIOR2_8_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior2.8 wavelet.
+pub type Bior2_8Wavelet = BiorthogonalWavelet<Bior2_8>;
+
+/// The Multi-Resolution Decomposition using the bior2.8 wavelet.
+pub type Bior2_8Decomposition = BiorthogonalDecomposition<Bior2_8>;
+
+/// The biorthogonal wavelet bior3.1, with a 4-coefficient reconstruction filter and a
+/// 4-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior3_1;
+
+impl Filter for Bior3_1 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR3_1, &BIOR3_1_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior3.1 wavelet.
+pub type Bior3_1Wavelet = BiorthogonalWavelet<Bior3_1>;
+
+/// The Multi-Resolution Decomposition using the bior3.1 wavelet.
+pub type Bior3_1Decomposition = BiorthogonalDecomposition<Bior3_1>;
+
+/// The biorthogonal wavelet bior3.3, with a 4-coefficient reconstruction filter and a
+/// 8-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior3_3;
+
+impl Filter for Bior3_3 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR3_3, &BIOR3_3_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior3.3 wavelet.
+pub type Bior3_3Wavelet = BiorthogonalWavelet<Bior3_3>;
+
+/// The Multi-Resolution Decomposition using the bior3.3 wavelet.
+pub type Bior3_3Decomposition = BiorthogonalDecomposition<Bior3_3>;
+
+/// The biorthogonal wavelet bior3.5, with a 4-coefficient reconstruction filter and a
+/// 12-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior3_5;
+
+impl Filter for Bior3_5 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR3_5, &BIOR3_5_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior3.5 wavelet.
+pub type Bior3_5Wavelet = BiorthogonalWavelet<Bior3_5>;
+
+/// The Multi-Resolution Decomposition using the bior3.5 wavelet.
+pub type Bior3_5Decomposition = BiorthogonalDecomposition<Bior3_5>;
+
+/// The biorthogonal wavelet bior3.7, with a 4-coefficient reconstruction filter and a
+/// 16-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior3_7;
+
+impl Filter for Bior3_7 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR3_7, &BIOR3_7_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior3.7 wavelet.
+pub type Bior3_7Wavelet = BiorthogonalWavelet<Bior3_7>;
+
+/// The Multi-Resolution Decomposition using the bior3.7 wavelet.
+pub type Bior3_7Decomposition = BiorthogonalDecomposition<Bior3_7>;
+
+/// The biorthogonal wavelet bior3.9, with a 4-coefficient reconstruction filter and a
+/// 20-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior3_9;
+
+impl Filter for Bior3_9 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR3_9, &BIOR3_9_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior3.9 wavelet.
+pub type Bior3_9Wavelet = BiorthogonalWavelet<Bior3_9>;
+
+/// The Multi-Resolution Decomposition using the bior3.9 wavelet.
+pub type Bior3_9Decomposition = BiorthogonalDecomposition<Bior3_9>;
+
+/// The biorthogonal wavelet bior4.4, with a 7-coefficient reconstruction filter and a
+/// 9-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior4_4;
+
+impl Filter for Bior4_4 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR4_4, &BIOR4_4_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior4.4 wavelet.
+pub type Bior4_4Wavelet = BiorthogonalWavelet<Bior4_4>;
+
+/// The Multi-Resolution Decomposition using the bior4.4 wavelet.
+pub type Bior4_4Decomposition = BiorthogonalDecomposition<Bior4_4>;
+
+/// The biorthogonal wavelet bior5.5, with a 10-coefficient reconstruction filter and a
+/// 10-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior5_5;
+
+impl Filter for Bior5_5 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR5_5, &BIOR5_5_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior5.5 wavelet.
+pub type Bior5_5Wavelet = BiorthogonalWavelet<Bior5_5>;
+
+/// The Multi-Resolution Decomposition using the bior5.5 wavelet.
+pub type Bior5_5Decomposition = BiorthogonalDecomposition<Bior5_5>;
+
+/// The biorthogonal wavelet bior6.8, with a 11-coefficient reconstruction filter and a
+/// 17-coefficient decomposition filter.
+#[derive(Debug)]
+pub struct Bior6_8;
+
+impl Filter for Bior6_8 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::biorthogonal(&BIOR6_8, &BIOR6_8_DUAL)
+    }
+}
+
+/// The Discrete Wavelet Transform using the bior6.8 wavelet.
+pub type Bior6_8Wavelet = BiorthogonalWavelet<Bior6_8>;
+
+/// The Multi-Resolution Decomposition using the bior6.8 wavelet.
+pub type Bior6_8Decomposition = BiorthogonalDecomposition<Bior6_8>;
+
+/// The CDF 5/3 wavelet (the LeGall 5/3 wavelet used for lossless JPEG 2000), which is bior2.2.
+pub type Cdf53 = Bior2_2;
+
+/// The CDF 9/7 wavelet used for lossy JPEG 2000, which is bior4.4.
+pub type Cdf97 = Bior4_4;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MRDecomposition, WaveletTransform};
+
+    fn tables() -> Vec<(&'static [f64], &'static [f64])> {
+        vec![
+            (&BIOR1_1, &BIOR1_1_DUAL),
+            (&BIOR1_3, &BIOR1_3_DUAL),
+            (&BIOR1_5, &BIOR1_5_DUAL),
+            (&BIOR2_2, &BIOR2_2_DUAL),
+            (&BIOR2_4, &BIOR2_4_DUAL),
+            (&BIOR2_6, &BIOR2_6_DUAL),
+            (&BIOR2_8, &BIOR2_8_DUAL),
+            (&BIOR3_1, &BIOR3_1_DUAL),
+            (&BIOR3_3, &BIOR3_3_DUAL),
+            (&BIOR3_5, &BIOR3_5_DUAL),
+            (&BIOR3_7, &BIOR3_7_DUAL),
+            (&BIOR3_9, &BIOR3_9_DUAL),
+            (&BIOR4_4, &BIOR4_4_DUAL),
+            (&BIOR5_5, &BIOR5_5_DUAL),
+            (&BIOR6_8, &BIOR6_8_DUAL),
+        ]
+    }
+
+    #[test]
+    fn filters_are_biorthogonal() {
+        for (scaling, dual) in tables() {
+            let bank = FilterBank::biorthogonal(scaling, dual);
+            assert!(
+                bank.is_biorthogonal(1e-14),
+                "Filters of length {} and {} are not biorthogonal!",
+                scaling.len(),
+                dual.len()
+            );
+            assert!(
+                bank.is_admissible(1e-14),
+                "Filters of length {} and {} are not admissible!",
+                scaling.len(),
+                dual.len()
+            );
+        }
+    }
+
+    #[test]
+    fn filters_are_symmetric() {
+        for (scaling, dual) in tables() {
+            for h in [scaling, dual] {
+                let reversed: Vec<f64> = h.iter().rev().copied().collect();
+                assert_eq!(
+                    h,
+                    &reversed[..],
+                    "Filter of length {} is not symmetric",
+                    h.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cdf97_matches_jpeg2000() {
+        let bank = Cdf97::filter_bank();
+        let expected = [
+            0.026748757411,
+            -0.016864118443,
+            -0.078223266529,
+            0.266864118443,
+            0.602949018236,
+        ];
+        // JPEG 2000 normalises the analysis low-pass filter to sum to 1 rather than sqrt(2)
+        let dual = bank.dual_scaling();
+        for (pos, expected) in expected.iter().enumerate() {
+            let actual = dual[pos] / std::f64::consts::SQRT_2;
+            assert!(
+                (expected - actual).abs() < 1e-11,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn bior1_1_is_haar() {
+        use crate::haar::HaarWavelet;
+
+        let data = [1_f64, 3.0, 5.0, 11.0, 12.0, 13.0, 0.0, 1.0];
+        let haar = HaarWavelet::transform(&data);
+        let bior = Bior1_1Wavelet::transform(&data);
+        assert_eq!(
+            haar.coefficients(),
+            bior.coefficients(),
+            "bior1.1 should be the Haar wavelet"
+        );
+    }
+
+    fn assert_round_trip<W, D>(data: &[f64])
+    where
+        W: WaveletTransform,
+        D: MRDecomposition<W>,
+    {
+        let delta = 1e-10;
+        let mut transforms = [
+            W::transform(data),
+            W::transform_in_place(data.to_vec()),
+            D::decompose(data).into(),
+        ];
+        for transform in transforms.iter_mut() {
+            for inverse in [transform.invert(), transform.invert_in_place()] {
+                for (pos, expected) in data.iter().enumerate() {
+                    assert!(
+                        (expected - inverse[pos]).abs() < delta,
+                        "Element at pos {} incorrect. Expected {} but was {}",
+                        pos,
+                        expected,
+                        inverse[pos]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transforms_invert() {
+        let data: Vec<f64> = (0..64).map(|v| ((v * 37) % 23) as f64 - 11.0).collect();
+
+        assert_round_trip::<Bior1_3Wavelet, Bior1_3Decomposition>(&data);
+        assert_round_trip::<Bior1_5Wavelet, Bior1_5Decomposition>(&data);
+        assert_round_trip::<Bior2_2Wavelet, Bior2_2Decomposition>(&data);
+        assert_round_trip::<Bior2_4Wavelet, Bior2_4Decomposition>(&data);
+        assert_round_trip::<Bior2_6Wavelet, Bior2_6Decomposition>(&data);
+        assert_round_trip::<Bior2_8Wavelet, Bior2_8Decomposition>(&data);
+        assert_round_trip::<Bior3_1Wavelet, Bior3_1Decomposition>(&data);
+        assert_round_trip::<Bior3_3Wavelet, Bior3_3Decomposition>(&data);
+        assert_round_trip::<Bior3_5Wavelet, Bior3_5Decomposition>(&data);
+        assert_round_trip::<Bior3_7Wavelet, Bior3_7Decomposition>(&data);
+        assert_round_trip::<Bior3_9Wavelet, Bior3_9Decomposition>(&data);
+        assert_round_trip::<Bior4_4Wavelet, Bior4_4Decomposition>(&data);
+        assert_round_trip::<Bior5_5Wavelet, Bior5_5Decomposition>(&data);
+        assert_round_trip::<Bior6_8Wavelet, Bior6_8Decomposition>(&data);
+    }
+}
@@ -0,0 +1,176 @@
+//! Boundary extension modes.
+//!
+//! Near the ends of the data, the filters of a wavelet transform read samples from beyond the
+//! end of the array. So we have to decide what those samples are, and there's no single right
+//! answer--it depends on what the data looks like. Whichever choice we make, the transform will
+//! pick up some artificial difference terms near the edges where the extension doesn't match
+//! what the data "really" does. The smaller the jump, the smaller those terms are.
+
+/// How to extend data beyond its ends.
+///
+/// Using data `[a, b, c, d]`, the modes extend as follows:
+///
+/// | mode            | before      | data         | after        |
+/// |-----------------|-------------|--------------|--------------|
+/// | `Zero`          | `0 0 0`     | `a b c d`    | `0 0 0`      |
+/// | `Constant`      | `a a a`     | `a b c d`    | `d d d`      |
+/// | `Symmetric`     | `c b a`     | `a b c d`    | `d c b`      |
+/// | `Reflect`       | `d c b`     | `a b c d`    | `c b a`      |
+/// | `Periodic`      | `b c d`     | `a b c d`    | `a b c`      |
+/// | `Smooth`        | linear      | `a b c d`    | linear       |
+/// | `Antisymmetric` | `-c -b -a`  | `a b c d`    | `-d -c -b`   |
+///
+/// `Smooth` extrapolates the straight line through the first (or last) two samples.
+///
+/// All of these except `Periodization` produce an _expansive_ transform: each level computes
+/// `floor((n + L - 1)/2)` averages and the same number of differences from `n` samples (for a
+/// filter of length `L`), which is every coefficient that the extended data contributes to. This
+/// is a few more coefficients than samples, but the inverse reconstructs the original data exactly.
+///
+/// `Periodization` treats the data as periodic too, but only keeps the `n/2` averages and
+/// differences of each level. This is the classic orthogonal transform: it has exactly as many
+/// coefficients as samples, and for an orthogonal wavelet it preserves the energy of the data. If
+/// `n` is odd, the last sample is repeated to make it even first, so there's one extra
+/// coefficient.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// Extend with zeros.
+    Zero,
+    /// Repeat the first and last samples.
+    Constant,
+    /// Reflect about the edge of the array, repeating the end samples (half-point symmetry).
+    Symmetric,
+    /// Reflect about the end samples, without repeating them (whole-point symmetry).
+    Reflect,
+    /// Repeat the data periodically.
+    Periodic,
+    /// Extrapolate linearly from the first and last two samples.
+    Smooth,
+    /// Reflect about the edge of the array, negating the reflected samples.
+    Antisymmetric,
+    /// Repeat the data periodically, keeping exactly as many coefficients as samples.
+    #[default]
+    Periodization,
+}
+
+impl BoundaryMode {
+    /// The value of the extended data at `index`, which can be anywhere outside of the data as
+    /// well as within it.
+    ///
+    /// The data must not be empty.
+    pub fn sample(self, data: &[f64], index: isize) -> f64 {
+        let len = data.len() as isize;
+        if (0..len).contains(&index) {
+            return data[index as usize];
+        }
+        match self {
+            BoundaryMode::Zero => 0.0,
+            BoundaryMode::Constant => {
+                if index < 0 {
+                    data[0]
+                } else {
+                    data[data.len() - 1]
+                }
+            }
+            BoundaryMode::Symmetric => {
+                let r = index.rem_euclid(2 * len);
+                data[if r < len { r } else { 2 * len - 1 - r } as usize]
+            }
+            BoundaryMode::Reflect => {
+                if len == 1 {
+                    return data[0];
+                }
+                let r = index.rem_euclid(2 * len - 2);
+                data[if r < len { r } else { 2 * len - 2 - r } as usize]
+            }
+            BoundaryMode::Periodic | BoundaryMode::Periodization => {
+                data[index.rem_euclid(len) as usize]
+            }
+            BoundaryMode::Smooth => {
+                if len == 1 {
+                    return data[0];
+                }
+                if index < 0 {
+                    data[0] + index as f64 * (data[1] - data[0])
+                } else {
+                    let last = data.len() - 1;
+                    data[last] + (index - len + 1) as f64 * (data[last] - data[last - 1])
+                }
+            }
+            BoundaryMode::Antisymmetric => {
+                let block = index.div_euclid(len);
+                let r = index.rem_euclid(len);
+                if block % 2 == 0 {
+                    data[r as usize]
+                } else {
+                    -data[(len - 1 - r) as usize]
+                }
+            }
+        }
+    }
+
+    /// The number of averages (and of differences) produced by a single level of the transform of
+    /// `len` samples with a filter of `filter_len` coefficients.
+    pub fn coefficients_len(self, len: usize, filter_len: usize) -> usize {
+        match self {
+            BoundaryMode::Periodization => len.div_ceil(2),
+            _ => (len + filter_len - 1) / 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundaryMode;
+
+    const DATA: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+
+    fn extend(mode: BoundaryMode) -> Vec<f64> {
+        (-3..7).map(|i| mode.sample(&DATA, i)).collect()
+    }
+
+    #[test]
+    fn modes_extend_correctly() {
+        let cases = [
+            (BoundaryMode::Zero, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            (BoundaryMode::Constant, [1.0, 1.0, 1.0, 8.0, 8.0, 8.0]),
+            (BoundaryMode::Symmetric, [4.0, 2.0, 1.0, 8.0, 4.0, 2.0]),
+            (BoundaryMode::Reflect, [8.0, 4.0, 2.0, 4.0, 2.0, 1.0]),
+            (BoundaryMode::Periodic, [2.0, 4.0, 8.0, 1.0, 2.0, 4.0]),
+            (BoundaryMode::Smooth, [-2.0, -1.0, 0.0, 12.0, 16.0, 20.0]),
+            (
+                BoundaryMode::Antisymmetric,
+                [-4.0, -2.0, -1.0, -8.0, -4.0, -2.0],
+            ),
+        ];
+        for (mode, expected) in cases {
+            let extended = extend(mode);
+            assert_eq!(&DATA, &extended[3..7], "{:?} changed the data!", mode);
+            assert_eq!(
+                &expected[..3],
+                &extended[..3],
+                "{:?} incorrect before",
+                mode
+            );
+            assert_eq!(&expected[3..], &extended[7..], "{:?} incorrect after", mode);
+        }
+    }
+
+    #[test]
+    fn extends_far_beyond_the_data() {
+        let data = [1.0, 2.0];
+        let reflected: Vec<f64> = (-5..0)
+            .map(|i| BoundaryMode::Symmetric.sample(&data, i))
+            .collect();
+        assert_eq!(
+            vec![1.0, 1.0, 2.0, 2.0, 1.0],
+            reflected,
+            "Incorrect reflections"
+        );
+        assert_eq!(
+            1.0,
+            BoundaryMode::Reflect.sample(&[1.0], 10),
+            "Single sample"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! The cascade algorithm, for evaluating the scaling function and wavelet of a filter bank.
+//!
+//! The scaling function of a filter bank is only defined implicitly, by the refinement equation
+//!
+//! ```text
+//! φ(x) = √2 Σ h_n φ(2x - n)
+//! ```
+//!
+//! (see `notes/cascade.md`). The cascade algorithm solves it by iteration: starting from a single
+//! spike at 0, each iteration applies the right-hand side to the previous approximation, halving
+//! the spacing of the samples. After `J` iterations, that's `φ` sampled on the dyadic grid
+//! `k/2^J`, which is equivalent to convolving the filters `h`, `h` upsampled by 2, by 4, and so on
+//! up to `2^(J-1)`. The wavelet then follows from its own equation, `ψ(x) = √2 Σ g_n φ(2x - n)`.
+//!
+//! For a valid filter bank the samples converge to `φ` and `ψ` as the number of iterations grows
+//! (more slowly for less smooth wavelets), so this is also a quick check of new coefficients:
+//! filters which don't satisfy the conditions for a wavelet give samples which blow up or never
+//! settle down.
+use crate::filter::FilterBank;
+
+/// The scaling function and wavelet sampled on a dyadic grid, as computed by [`cascade`].
+///
+/// The `k`th sample is at `x = k/2^J`, so the grid starts at 0, the start of the filters'
+/// support (any leading zeros in the filters, as biorthogonal filters may have, shift the
+/// functions along).
+#[derive(Debug, Clone)]
+pub struct Cascade {
+    step: f64,
+    scaling: Vec<f64>,
+    wavelet: Vec<f64>,
+}
+
+impl Cascade {
+    /// The spacing of the grid, `2^-J`.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// The positions of the samples.
+    pub fn x(&self) -> Vec<f64> {
+        (0..self.scaling.len())
+            .map(|k| k as f64 * self.step)
+            .collect()
+    }
+
+    /// The samples of the scaling function `φ`.
+    pub fn scaling(&self) -> &[f64] {
+        &self.scaling
+    }
+
+    /// The samples of the wavelet `ψ`.
+    pub fn wavelet(&self) -> &[f64] {
+        &self.wavelet
+    }
+
+    /// The `p`th moment of the scaling function, `∫ x^p φ(x) dx`, computed from the samples.
+    pub fn scaling_moment(&self, p: i32) -> f64 {
+        moment(&self.scaling, self.step, p)
+    }
+
+    /// The `p`th moment of the wavelet, `∫ x^p ψ(x) dx`, computed from the samples.
+    pub fn wavelet_moment(&self, p: i32) -> f64 {
+        moment(&self.wavelet, self.step, p)
+    }
+}
+
+fn moment(samples: &[f64], step: f64, p: i32) -> f64 {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(k, v)| v * (k as f64 * step).powi(p))
+        .sum::<f64>()
+        * step
+}
+
+/// Evaluate the (reconstruction) scaling function `φ` and wavelet `ψ` of a filter bank on the
+/// grid `k/2^J`, with `J = iterations` (at least 1).
+pub fn cascade(bank: &FilterBank, iterations: usize) -> Cascade {
+    cascade_filters(bank.scaling(), bank.wavelet(), iterations)
+}
+
+/// Evaluate the dual (decomposition) scaling function `~φ` and wavelet `~ψ` of a filter bank, as
+/// in [`cascade`]. For an orthogonal filter bank these are the same as the primal ones.
+pub fn dual_cascade(bank: &FilterBank, iterations: usize) -> Cascade {
+    cascade_filters(bank.dual_scaling(), bank.dual_wavelet(), iterations)
+}
+
+fn cascade_filters(scaling: &[f64], wavelet: &[f64], iterations: usize) -> Cascade {
+    let iterations = iterations.max(1);
+    let mut phi = vec![1.0];
+    for i in 0..iterations - 1 {
+        phi = refine(&phi, scaling, 1 << i);
+    }
+    let psi = refine(&phi, wavelet, 1 << (iterations - 1));
+    let phi = refine(&phi, scaling, 1 << (iterations - 1));
+    Cascade {
+        step: 1.0 / (1u64 << iterations) as f64,
+        scaling: phi,
+        wavelet: psi,
+    }
+}
+
+/// `√2 (samples * filter upsampled by spacing)`, one iteration of the refinement equation.
+fn refine(samples: &[f64], filter: &[f64], spacing: usize) -> Vec<f64> {
+    let mut refined = vec![0.0; samples.len() + (filter.len() - 1) * spacing];
+    for (n, h) in filter.iter().enumerate() {
+        for (k, v) in samples.iter().enumerate() {
+            refined[k + n * spacing] += std::f64::consts::SQRT_2 * h * v;
+        }
+    }
+    refined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biorthogonal::Bior2_2;
+    use crate::daubechies::Daub4;
+    use crate::filter::Filter;
+    use crate::haar::Haar;
+
+    #[test]
+    fn haar_is_a_box() {
+        let haar = cascade(&Haar::filter_bank(), 3);
+        assert_eq!(0.125, haar.step());
+        assert_eq!(
+            vec![1.0; 8],
+            haar.scaling().iter().map(|v| v.round()).collect::<Vec<_>>()
+        );
+        for (k, v) in haar.wavelet().iter().enumerate() {
+            let expected = if k < 4 { 1.0 } else { -1.0 };
+            assert!((expected - v).abs() < 1e-12, "ψ({}) = {}", k, v);
+        }
+    }
+
+    #[test]
+    fn converges_to_daub4() {
+        // φ(1) = (1+√3)/2 and φ(2) = (1-√3)/2, although Daub4 isn't very smooth so the samples
+        // only creep towards them
+        let daub4 = cascade(&Daub4::filter_bank(), 14);
+        let at = |x: usize| daub4.scaling()[x << 14];
+        let root3 = 3f64.sqrt();
+        assert!(
+            (at(1) - (1.0 + root3) / 2.0).abs() < 2e-2,
+            "φ(1) = {}",
+            at(1)
+        );
+        assert!(
+            (at(2) - (1.0 - root3) / 2.0).abs() < 2e-2,
+            "φ(2) = {}",
+            at(2)
+        );
+        assert!(daub4.x().last().unwrap() < &3.0);
+    }
+
+    #[test]
+    fn computes_moments() {
+        let bank = Daub4::filter_bank();
+        let daub4 = cascade(&bank, 12);
+        assert!((daub4.scaling_moment(0) - 1.0).abs() < 1e-12);
+
+        // the first moment of φ is Σ n h_n / √2, up to the resolution of the grid
+        let expected: f64 = bank
+            .scaling()
+            .iter()
+            .enumerate()
+            .map(|(n, h)| n as f64 * h)
+            .sum::<f64>()
+            / std::f64::consts::SQRT_2;
+        assert!((daub4.scaling_moment(1) - expected).abs() < 2.0 * daub4.step());
+
+        // Daub4 has two vanishing moments
+        assert!(daub4.wavelet_moment(0).abs() < 1e-10);
+        assert!(daub4.wavelet_moment(1).abs() < 1e-10);
+        assert!(daub4.wavelet_moment(2).abs() > 1e-2);
+    }
+
+    #[test]
+    fn biorthogonal_functions_differ() {
+        // the reconstruction scaling function of bior2.2 is the hat function
+        let bank = Bior2_2::filter_bank();
+        let primal = cascade(&bank, 4);
+        let dual = dual_cascade(&bank, 4);
+        let peak = primal.scaling().iter().cloned().fold(f64::MIN, f64::max);
+        assert!(
+            (peak - 1.0).abs() < 1e-12,
+            "Hat should peak at 1, not {}",
+            peak
+        );
+        assert!(primal.scaling().iter().all(|v| *v >= -1e-12));
+        assert!(dual.scaling().iter().any(|v| *v < -1e-3));
+        assert!((dual.scaling_moment(0) - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,282 @@
+// The Coiflet family of orthogonal wavelets.
+use crate::filter::{Filter, FilterBank};
+use crate::wavelet::{OrthogonalDecomposition, OrthogonalWavelet};
+
+// Scaling coefficients for the Coiflets. The coiflet of order K has 6K coefficients, chosen so that
+// the wavelet has 2K vanishing moments and the scaling function has 2K-1 vanishing moments (other
+// than the zeroth, which must be 1). In terms of the coefficients, that means that as well as the
+// normality and orthogonality conditions from `matrix.md`, we need
+// `sum_n (-1)^n n^p h_n = 0` for `p < 2K` and `sum_n n^p h_n = 0` for `0 < p < 2K`, where `n` runs
+// from `-2K` to `4K-1`. These were found by solving that system numerically.
+
+const COIF1: [f64; 6] = [
+    -0.015655728135791993,
+    -0.07273261951252645,
+    0.3848648468648577,
+    0.8525720202116004,
+    0.33789766245748176,
+    -0.07273261951252645,
+];
+
+const COIF2: [f64; 12] = [
+    -0.000720549445520347,
+    -0.001823208870911032,
+    0.005611434819368834,
+    0.02368017194684777,
+    -0.059434418646431085,
+    -0.07648859907828076,
+    0.41700518442323903,
+    0.8127236354494135,
+    0.38611006682276283,
+    -0.0673725547237256,
+    -0.04146493678687178,
+    0.01638733646320364,
+];
+
+const COIF3: [f64; 18] = [
+    -3.4599773197272774e-05,
+    -7.0983302506379e-05,
+    0.0004662169598204029,
+    0.0011175187708306303,
+    -0.002574517688136797,
+    -0.009007976136730624,
+    0.015880544863669452,
+    0.03455502757329773,
+    -0.08230192710629981,
+    -0.07179982161915484,
+    0.42848347637737,
+    0.7937772226260872,
+    0.4051769024091182,
+    -0.06112339000297254,
+    -0.06577191128146936,
+    0.023452696142077165,
+    0.0077825964256727454,
+    -0.0037935128643808015,
+];
+
+const COIF4: [f64; 24] = [
+    -1.7849909144933466e-06,
+    -3.2596479400307506e-06,
+    3.1229861599195265e-05,
+    6.233885431278718e-05,
+    -0.0002599743371222568,
+    -0.0005890202246332164,
+    0.0012665610789256603,
+    0.003751434697146086,
+    -0.0056582838001308835,
+    -0.015211728187697211,
+    0.025082253337949608,
+    0.03933442260558915,
+    -0.09622042453595264,
+    -0.06662747236681715,
+    0.43438603311435653,
+    0.7822389344242826,
+    0.41530842700068227,
+    -0.05607731960356926,
+    -0.08126671024919373,
+    0.026682304669604834,
+    0.016068947131575025,
+    -0.00734616793626805,
+    -0.0016294924252267858,
+    0.000892313902537003,
+];
+
+const COIF5: [f64; 30] = [
+    -9.604010112767892e-08,
+    -1.6237995172048335e-07,
+    2.0612203985788783e-06,
+    3.7007277113394796e-06,
+    -2.1270221672515614e-05,
+    -4.12198619242655e-05,
+    0.00014035632812373243,
+    0.00030185794166824473,
+    -0.0006375589261258812,
+    -0.0016616273039298788,
+    0.0024315754425382886,
+    0.006761520220620417,
+    -0.009159507338676163,
+    -0.019758391600965465,
+    0.03267479946705735,
+    0.041287530472117834,
+    -0.10556315130733723,
+    -0.06203775157498195,
+    0.4379823066591633,
+    0.7742936228603274,
+    0.42157126673075435,
+    -0.05204667025355476,
+    -0.09192158806008609,
+    0.028169744270532353,
+    0.023408322118927783,
+    -0.010131584846900275,
+    -0.004159312627578639,
+    0.0021782943778456947,
+    0.0003585777411617577,
+    -0.000212081862067494,
+];
+
+/// The Coiflet of order 1, which has 6 coefficients and 2 vanishing moments.
+#[derive(Debug)]
+pub struct Coif1;
+
+impl Filter for Coif1 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&COIF1)
+    }
+}
+
+/// The Discrete Wavelet Transform using the coif1 wavelet.
+pub type Coif1Wavelet = OrthogonalWavelet<Coif1>;
+
+/// The Multi-Resolution Decomposition using the coif1 wavelet.
+pub type Coif1Decomposition = OrthogonalDecomposition<Coif1>;
+
+/// The Coiflet of order 2, which has 12 coefficients and 4 vanishing moments.
+#[derive(Debug)]
+pub struct Coif2;
+
+impl Filter for Coif2 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&COIF2)
+    }
+}
+
+/// The Discrete Wavelet Transform using the coif2 wavelet.
+pub type Coif2Wavelet = OrthogonalWavelet<Coif2>;
+
+/// The Multi-Resolution Decomposition using the coif2 wavelet.
+pub type Coif2Decomposition = OrthogonalDecomposition<Coif2>;
+
+/// The Coiflet of order 3, which has 18 coefficients and 6 vanishing moments.
+#[derive(Debug)]
+pub struct Coif3;
+
+impl Filter for Coif3 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&COIF3)
+    }
+}
+
+/// The Discrete Wavelet Transform using the coif3 wavelet.
+pub type Coif3Wavelet = OrthogonalWavelet<Coif3>;
+
+/// The Multi-Resolution Decomposition using the coif3 wavelet.
+pub type Coif3Decomposition = OrthogonalDecomposition<Coif3>;
+
+/// The Coiflet of order 4, which has 24 coefficients and 8 vanishing moments.
+#[derive(Debug)]
+pub struct Coif4;
+
+impl Filter for Coif4 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&COIF4)
+    }
+}
+
+/// The Discrete Wavelet Transform using the coif4 wavelet.
+pub type Coif4Wavelet = OrthogonalWavelet<Coif4>;
+
+/// The Multi-Resolution Decomposition using the coif4 wavelet.
+pub type Coif4Decomposition = OrthogonalDecomposition<Coif4>;
+
+/// The Coiflet of order 5, which has 30 coefficients and 10 vanishing moments.
+#[derive(Debug)]
+pub struct Coif5;
+
+impl Filter for Coif5 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&COIF5)
+    }
+}
+
+/// The Discrete Wavelet Transform using the coif5 wavelet.
+pub type Coif5Wavelet = OrthogonalWavelet<Coif5>;
+
+/// The Multi-Resolution Decomposition using the coif5 wavelet.
+pub type Coif5Decomposition = OrthogonalDecomposition<Coif5>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MRDecomposition, WaveletTransform};
+
+    fn banks() -> Vec<FilterBank> {
+        vec![
+            Coif1::filter_bank(),
+            Coif2::filter_bank(),
+            Coif3::filter_bank(),
+            Coif4::filter_bank(),
+            Coif5::filter_bank(),
+        ]
+    }
+
+    #[test]
+    fn coefficients_are_orthonormal() {
+        for bank in banks() {
+            assert!(
+                bank.is_admissible(1e-14),
+                "coif{} is not admissible!",
+                bank.len() / 6
+            );
+            assert!(
+                bank.is_orthonormal(1e-14),
+                "coif{} is not orthonormal!",
+                bank.len() / 6
+            );
+        }
+    }
+
+    #[test]
+    fn wavelet_and_scaling_moments_vanish() {
+        for bank in banks() {
+            let order = bank.len() / 6;
+            assert!(
+                bank.vanishing_moments(1e-10) >= 2 * order,
+                "coif{} only has {} vanishing moments!",
+                order,
+                bank.vanishing_moments(1e-10)
+            );
+
+            // the coefficients are stored in reverse, so h_n for n in -2K..4K is at position 4K-1-n
+            let h = bank.scaling();
+            for p in 1..2 * order as i32 {
+                let terms = h.iter().enumerate().map(|(pos, v)| {
+                    let n = 4.0 * order as f64 - 1.0 - pos as f64;
+                    v * n.powi(p)
+                });
+                let (moment, scale) = terms.fold((0.0, 0.0), |(m, s), t| (m + t, s + t.abs()));
+                assert!(
+                    moment.abs() < 1e-10 * scale,
+                    "coif{} scaling moment {} does not vanish: {}",
+                    order,
+                    p,
+                    moment
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transforms_invert() {
+        let data: Vec<f64> = (0..64).map(|v| ((v * 37) % 23) as f64 - 11.0).collect();
+
+        let wavelet = Coif3Wavelet::transform(&data);
+        let decomposed: Coif3Wavelet = Coif3Decomposition::decompose(&data).into();
+        assert_eq!(
+            wavelet.coefficients(),
+            decomposed.coefficients(),
+            "Decomposition does not match the transform!"
+        );
+
+        let inverse = wavelet.invert();
+        let delta = 1e-10;
+        for (pos, expected) in data.iter().enumerate() {
+            assert!(
+                (expected - inverse[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                inverse[pos]
+            );
+        }
+    }
+}
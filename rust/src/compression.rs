@@ -0,0 +1,289 @@
+//! Lossy compression by discarding the smallest wavelet coefficients.
+//!
+//! This is the compression algorithm in `notes/operations.md`: transform the data, keep only the
+//! coefficients which matter, and invert what's left. Rather than a fixed threshold, the
+//! coefficients to keep are chosen by a [`Budget`], always keeping the largest ones first.
+//!
+//! For an orthogonal wavelet (like Haar) with [`BoundaryMode::Periodization`], the transform is
+//! orthonormal, so the squared error of the reconstruction is exactly the energy of the
+//! coefficients that were dropped. That makes it possible to keep the fewest coefficients which
+//! meet an error budget without reconstructing anything, and the budget is guaranteed to be met.
+//! Other transforms aren't orthonormal (including Haar, when the data isn't a power of 2 long and
+//! has to be padded), so the error is measured by reconstructing the data instead. The budget is
+//! still always met: if the coefficients the energy would allow don't reconstruct the data closely
+//! enough, a binary search finds how many more of the largest to keep, which takes `O(log N)`
+//! reconstructions.
+//!
+//! Only the filter-bank transforms ([`FilterWavelet`]) can be compressed. A lifting transform
+//! ([`crate::lifting::LiftingWavelet`]) computes the same coefficients as the [`FilterWavelet`] of
+//! its filter with [`BoundaryMode::Periodization`], so compress that instead.
+use std::marker::PhantomData;
+
+use crate::boundary::BoundaryMode;
+use crate::filter::Filter;
+use crate::wavelet::FilterWavelet;
+use crate::DetailCoefficients;
+use crate::WaveletTransform;
+
+/// How many coefficients to keep when compressing a transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    /// Keep this many of the largest coefficients (by magnitude).
+    Largest(usize),
+    /// Keep as few of the largest coefficients as reconstruct the data with at most this L2 error.
+    Error(f64),
+    /// Keep the fewest coefficients which hold at least this fraction of the transform's energy.
+    Energy(f64),
+}
+
+/// A transform with only some of its coefficients kept.
+#[derive(Debug, Clone)]
+pub struct CompressedWavelet<F: Filter> {
+    coefficients: Vec<(usize, f64)>,
+    total: usize,
+    len: usize,
+    mode: BoundaryMode,
+    levels: usize,
+    error: f64,
+    retained_energy: f64,
+    filter: PhantomData<F>,
+}
+
+impl<F: Filter> CompressedWavelet<F> {
+    /// The coefficients which were kept, as `(position, value)` in order of position (where the
+    /// position is in [`FilterWavelet::coefficients`]).
+    pub fn coefficients(&self) -> &[(usize, f64)] {
+        &self.coefficients
+    }
+
+    /// The number of samples the transform was computed from.
+    pub fn signal_len(&self) -> usize {
+        self.len
+    }
+
+    /// The boundary mode used to compute the transform.
+    pub fn mode(&self) -> BoundaryMode {
+        self.mode
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// The L2 distance between the original data and its reconstruction from the compressed
+    /// coefficients.
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// The fraction of the transform's energy held by the coefficients that were kept.
+    pub fn retained_energy(&self) -> f64 {
+        self.retained_energy
+    }
+
+    /// The transform, with all of the coefficients that weren't kept set to zero.
+    pub fn decompress(&self) -> FilterWavelet<F> {
+        let mut coefficients = vec![0.0; self.total];
+        for &(pos, value) in &self.coefficients {
+            coefficients[pos] = value;
+        }
+        FilterWavelet::try_from_coefficients(coefficients, self.len, self.mode, self.levels)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Reconstruct the data from the compressed coefficients.
+    pub fn invert(&self) -> Vec<f64> {
+        self.decompress().invert_in_place()
+    }
+}
+
+impl<F: Filter> FilterWavelet<F> {
+    /// Compress the transform, keeping the coefficients allowed by `budget`.
+    pub fn compress(&self, budget: Budget) -> CompressedWavelet<F> {
+        let coefficients = self.coefficients();
+        let energy: f64 = coefficients.iter().map(|c| c * c).sum();
+
+        // smallest first, so that the coefficients to drop are a prefix
+        let mut order: Vec<usize> = (0..coefficients.len()).collect();
+        order.sort_by(|&a, &b| coefficients[a].abs().total_cmp(&coefficients[b].abs()));
+
+        let squares: Vec<f64> = order.iter().map(|&pos| coefficients[pos].powi(2)).collect();
+        let dropped = match budget {
+            Budget::Largest(keep) => coefficients.len().saturating_sub(keep),
+            Budget::Error(error) => droppable(&squares, error * error),
+            Budget::Energy(fraction) => droppable(&squares, (1.0 - fraction) * energy),
+        };
+        let compress = |dropped: usize| {
+            let dropped_energy: f64 = squares[..dropped].iter().sum();
+            let mut kept: Vec<(usize, f64)> = order[dropped..]
+                .iter()
+                .map(|&pos| (pos, coefficients[pos]))
+                .collect();
+            kept.sort_by_key(|&(pos, _)| pos);
+            CompressedWavelet {
+                coefficients: kept,
+                total: coefficients.len(),
+                len: self.signal_len(),
+                mode: self.mode(),
+                levels: self.levels(),
+                error: dropped_energy.sqrt(),
+                retained_energy: if energy > 0.0 {
+                    1.0 - dropped_energy / energy
+                } else {
+                    1.0
+                },
+                filter: PhantomData,
+            }
+        };
+
+        // without padding, an orthogonal wavelet with periodization is orthonormal, so the error
+        // is just the energy which was dropped. Otherwise, measure it.
+        let orthonormal = F::filter_bank().is_orthogonal()
+            && self.mode() == BoundaryMode::Periodization
+            && coefficients.len() == self.signal_len();
+        if orthonormal {
+            return compress(dropped);
+        }
+        let original = self.invert();
+        let measured = |dropped: usize| {
+            let mut compressed = compress(dropped);
+            let reconstructed = compressed.invert();
+            compressed.error = original
+                .iter()
+                .zip(&reconstructed)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f64>()
+                .sqrt();
+            compressed
+        };
+        let mut compressed = measured(dropped);
+        if let Budget::Error(error) = budget {
+            if compressed.error > error {
+                // the error grows as more coefficients are dropped, so search for the most that
+                // can be dropped below the estimate (dropping none reconstructs the data exactly)
+                let (mut within, mut over) = (0, dropped);
+                while over - within > 1 {
+                    let mid = within + (over - within) / 2;
+                    if measured(mid).error <= error {
+                        within = mid;
+                    } else {
+                        over = mid;
+                    }
+                }
+                compressed = measured(within);
+            }
+        }
+        compressed
+    }
+}
+
+/// How many of the (ascending) squares can be dropped before their sum goes over `allowance`.
+fn droppable(squares: &[f64], allowance: f64) -> usize {
+    let mut sum = 0.0;
+    squares
+        .iter()
+        .take_while(|&&square| {
+            sum += square;
+            sum <= allowance
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biorthogonal::Bior2_2;
+    use crate::haar::HaarWavelet;
+
+    const DATA: [f64; 8] = [1.0, 3.0, 5.0, 11.0, 12.0, 13.0, 0.0, 1.0];
+
+    fn distance(a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn keeps_largest_coefficients() {
+        let haar = HaarWavelet::transform(&DATA);
+        let compressed = haar.compress(Budget::Largest(3));
+        assert_eq!(3, compressed.coefficients().len());
+
+        // [23/√2, -3/√2, -6, 12, -2, -6, -1, -1]: the largest are 23/√2, 12 and one of the -6s
+        let positions: Vec<usize> = compressed.coefficients().iter().map(|c| c.0).collect();
+        assert_eq!(0, positions[0]);
+        assert!(positions.contains(&3));
+
+        let reconstructed = compressed.invert();
+        assert!((distance(&DATA, &reconstructed) - compressed.error()).abs() < 1e-12);
+        let kept = compressed.decompress();
+        assert_eq!(5, kept.coefficients().iter().filter(|c| **c == 0.0).count());
+
+        assert_eq!(8, haar.compress(Budget::Largest(20)).coefficients().len());
+        assert_eq!(0.0, haar.compress(Budget::Largest(20)).error());
+    }
+
+    #[test]
+    fn meets_error_budget() {
+        let data: Vec<f64> = (0..64)
+            .map(|i| ((i * i) % 17) as f64 + i as f64 / 4.0)
+            .collect();
+        let haar = HaarWavelet::transform(&data);
+        for budget in [0.5, 2.0, 5.0, 20.0] {
+            let compressed = haar.compress(Budget::Error(budget));
+            let error = distance(&data, &compressed.invert());
+            assert!(error <= budget + 1e-9, "{} over budget {}", error, budget);
+            assert!((error - compressed.error()).abs() < 1e-9);
+
+            // and it's the fewest which do
+            let fewer = haar.compress(Budget::Largest(compressed.coefficients().len() - 1));
+            assert!(fewer.error() > budget);
+        }
+    }
+
+    #[test]
+    fn retains_energy() {
+        let haar = HaarWavelet::transform(&DATA);
+        let compressed = haar.compress(Budget::Energy(0.99));
+        assert!(compressed.retained_energy() >= 0.99);
+        let fewer = haar.compress(Budget::Largest(compressed.coefficients().len() - 1));
+        assert!(fewer.retained_energy() < 0.99);
+        let energy: f64 = DATA.iter().map(|v| v * v).sum();
+        let dropped = compressed.error().powi(2) / energy;
+        assert!((compressed.retained_energy() + dropped - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn measures_error_of_other_transforms() {
+        // neither biorthogonal nor padded transforms are orthonormal
+        let bior = FilterWavelet::<Bior2_2>::transform(&DATA);
+        let compressed = bior.compress(Budget::Largest(4));
+        assert!((distance(&DATA, &compressed.invert()) - compressed.error()).abs() < 1e-12);
+
+        let haar = HaarWavelet::transform(&DATA[..7]);
+        let compressed = haar.compress(Budget::Largest(4));
+        assert!((distance(&DATA[..7], &compressed.invert()) - compressed.error()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn meets_error_budget_of_other_transforms() {
+        let data: Vec<f64> = (0..50)
+            .map(|i| ((i * i) % 17) as f64 + i as f64 / 4.0)
+            .collect();
+        let haar = HaarWavelet::transform(&data);
+        let bior = FilterWavelet::<Bior2_2>::transform(&data);
+        for budget in [0.5, 2.0, 5.0, 20.0] {
+            let compressed = [
+                (haar.compress(Budget::Error(budget)).invert(), "haar"),
+                (bior.compress(Budget::Error(budget)).invert(), "bior2.2"),
+            ];
+            for (reconstructed, name) in compressed {
+                let error = distance(&data, &reconstructed);
+                assert!(error <= budget, "{} {} over budget {}", name, error, budget);
+            }
+        }
+    }
+}
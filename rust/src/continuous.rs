@@ -0,0 +1,372 @@
+//! The Continuous Wavelet Transform.
+//!
+//! Where the discrete transforms work with a fixed ladder of dyadic scales, the continuous
+//! transform correlates the signal with the mother wavelet stretched to any scale `s`:
+//!
+//! ```text
+//! W(s, n) = Σ x[n'] ψ*((n' - n)/s) / √s
+//! ```
+//!
+//! which gives a scale × time picture of the signal (a _scalogram_), at the cost of a great deal
+//! of redundancy. Computed directly, that's `O(N²)` per scale, so this follows Torrence and Compo
+//! (_A Practical Guide to Wavelet Analysis_, 1998) and computes it as a product in the frequency
+//! domain instead, in `O(N log N)` per scale. The signal is padded with zeros to at least twice its
+//! length, so that the ends of the signal don't wrap around into each other.
+//!
+//! Scales are measured in samples. Each mother wavelet has a _Fourier factor_ relating its scale to
+//! the period of the sinusoid it responds to most strongly, which gives the conversion between
+//! scales and (pseudo-)frequencies.
+use std::f64::consts::PI;
+
+use crate::complex::Complex;
+use crate::error::{self, WaveletError};
+use crate::fft;
+
+/// The mother wavelets of the continuous transform.
+///
+/// Each is normalized to unit energy, so the coefficients at different scales are comparable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotherWavelet {
+    /// The complex Morlet wavelet `π^(-1/4) e^(iω₀t) e^(-t²/2)`, a Gaussian-windowed complex
+    /// sinusoid. `omega0` is the number of radians per unit time of the sinusoid; 6 is the usual
+    /// choice, and gives a good balance between time and frequency resolution.
+    Morlet { omega0: f64 },
+    /// The Mexican hat (or Ricker) wavelet `(1 - t²) e^(-t²/2)`, the second derivative of a
+    /// Gaussian (up to sign and normalization). It's real, and responds to peaks and troughs.
+    MexicanHat,
+    /// The complex Paul wavelet of the given order (at least 1), which has good time resolution
+    /// but poor frequency resolution.
+    Paul { order: u32 },
+    /// The `order`th derivative of a Gaussian, which is real. The order must be at least 1, since
+    /// the Gaussian itself doesn't have a zero mean.
+    DerivativeOfGaussian { order: u32 },
+}
+
+impl MotherWavelet {
+    /// The Fourier transform of the wavelet at `omega` (already multiplied by the scale), scaled
+    /// to unit energy.
+    ///
+    /// This panics if the wavelet has an order of 0, which doesn't make it a wavelet.
+    pub fn frequency_response(&self, omega: f64) -> Complex {
+        self.try_frequency_response(omega)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The Fourier transform of the wavelet at `omega`, as in
+    /// [`MotherWavelet::frequency_response`], or an error if the wavelet has an order of 0.
+    pub fn try_frequency_response(&self, omega: f64) -> Result<Complex, WaveletError> {
+        self.check_order()?;
+        Ok(self.response(omega))
+    }
+
+    /// The Fourier transform of a wavelet whose order has already been checked.
+    fn response(&self, omega: f64) -> Complex {
+        match *self {
+            MotherWavelet::Morlet { omega0 } if omega > 0.0 => {
+                let shift = omega - omega0;
+                Complex::from(PI.powf(-0.25) * (-shift * shift / 2.0).exp())
+            }
+            MotherWavelet::Paul { order } if omega > 0.0 => {
+                let m = order as i32;
+                let norm = 2f64.powi(m) / (order as f64 * factorial(2 * order - 1)).sqrt();
+                Complex::from(norm * omega.powi(m) * (-omega).exp())
+            }
+            MotherWavelet::Morlet { .. } | MotherWavelet::Paul { .. } => Complex::default(),
+            MotherWavelet::MexicanHat => {
+                MotherWavelet::DerivativeOfGaussian { order: 2 }.response(omega)
+            }
+            MotherWavelet::DerivativeOfGaussian { order } => {
+                let norm = gamma_half(order).sqrt();
+                let value = omega.powi(order as i32) * (-omega * omega / 2.0).exp() / norm;
+                // -i^m
+                match order % 4 {
+                    0 => Complex::new(-value, 0.0),
+                    1 => Complex::new(0.0, -value),
+                    2 => Complex::new(value, 0.0),
+                    _ => Complex::new(0.0, value),
+                }
+            }
+        }
+    }
+
+    /// Check that the wavelet's order (if it has one) makes it a wavelet.
+    fn check_order(&self) -> Result<(), WaveletError> {
+        match *self {
+            MotherWavelet::Paul { order: 0 } | MotherWavelet::DerivativeOfGaussian { order: 0 } => {
+                Err(WaveletError::InvalidOrder { order: 0 })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The ratio of the period of the sinusoid the wavelet responds to most strongly to the
+    /// wavelet's scale.
+    pub fn fourier_factor(&self) -> f64 {
+        match *self {
+            MotherWavelet::Morlet { omega0 } => {
+                4.0 * PI / (omega0 + (2.0 + omega0 * omega0).sqrt())
+            }
+            MotherWavelet::MexicanHat => {
+                MotherWavelet::DerivativeOfGaussian { order: 2 }.fourier_factor()
+            }
+            MotherWavelet::Paul { order } => 4.0 * PI / (2 * order + 1) as f64,
+            MotherWavelet::DerivativeOfGaussian { order } => 2.0 * PI / (order as f64 + 0.5).sqrt(),
+        }
+    }
+
+    /// The pseudo-frequency which a scale (in samples) corresponds to, in the units of
+    /// `sample_rate`.
+    pub fn scale_to_frequency(&self, scale: f64, sample_rate: f64) -> f64 {
+        sample_rate / (self.fourier_factor() * scale)
+    }
+
+    /// The scale (in samples) which corresponds to a pseudo-frequency, in the units of
+    /// `sample_rate`.
+    pub fn frequency_to_scale(&self, frequency: f64, sample_rate: f64) -> f64 {
+        sample_rate / (self.fourier_factor() * frequency)
+    }
+}
+
+/// `n!`
+fn factorial(n: u32) -> f64 {
+    (1..=n).map(f64::from).product()
+}
+
+/// `Γ(m + 1/2)`
+fn gamma_half(m: u32) -> f64 {
+    (0..m).fold(PI.sqrt(), |gamma, k| gamma * (k as f64 + 0.5))
+}
+
+/// The Continuous Wavelet Transform of a signal at a set of scales.
+///
+/// The coefficients are held as a scale × time matrix, one row per scale.
+#[derive(Debug)]
+pub struct ContinuousWavelet {
+    coefficients: Vec<Complex>,
+    scales: Vec<f64>,
+    len: usize,
+    wavelet: MotherWavelet,
+}
+
+impl ContinuousWavelet {
+    /// Transform the data at each of the scales (in samples).
+    ///
+    /// This panics if any of the scales isn't positive and finite, or the wavelet's order is 0.
+    pub fn transform<T>(data: &[T], scales: &[f64], wavelet: MotherWavelet) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::try_transform(data, scales, wavelet).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Transform the data at each of the scales (in samples), or return an error if the data
+    /// isn't finite, one of the scales isn't positive and finite, or the wavelet's order is 0.
+    pub fn try_transform<T>(
+        data: &[T],
+        scales: &[f64],
+        wavelet: MotherWavelet,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        if let Some(index) = scales.iter().position(|s| !(s.is_finite() && *s > 0.0)) {
+            return Err(WaveletError::InvalidScale { index });
+        }
+        wavelet.check_order()?;
+
+        let len = data.len();
+        let padded = (2 * len).next_power_of_two();
+        let mut spectrum = vec![Complex::default(); padded];
+        for (s, v) in spectrum.iter_mut().zip(data) {
+            *s = Complex::from((*v).into());
+        }
+        fft::fft(&mut spectrum);
+
+        let mut coefficients = Vec::with_capacity(len * scales.len());
+        let mut row = vec![Complex::default(); padded];
+        for &scale in scales {
+            let norm = (2.0 * PI * scale).sqrt();
+            for (k, (r, x)) in row.iter_mut().zip(&spectrum).enumerate() {
+                let omega = angular_frequency(k, padded);
+                *r = *x * wavelet.response(scale * omega).conj() * norm;
+            }
+            fft::ifft(&mut row);
+            coefficients.extend_from_slice(&row[..len]);
+        }
+        Ok(ContinuousWavelet {
+            coefficients,
+            scales: scales.to_vec(),
+            len,
+            wavelet,
+        })
+    }
+
+    /// The scales the data was transformed at, in samples.
+    pub fn scales(&self) -> &[f64] {
+        &self.scales
+    }
+
+    /// The length of the data.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the data was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The mother wavelet.
+    pub fn wavelet(&self) -> MotherWavelet {
+        self.wavelet
+    }
+
+    /// The whole scale × time matrix of coefficients, in row-major order.
+    pub fn coefficients(&self) -> &[Complex] {
+        &self.coefficients
+    }
+
+    /// The coefficients at the scale with the given index, or `None` if there's no such scale.
+    pub fn row(&self, scale: usize) -> Option<&[Complex]> {
+        if scale >= self.scales.len() {
+            return None;
+        }
+        Some(&self.coefficients[scale * self.len..(scale + 1) * self.len])
+    }
+
+    /// The pseudo-frequency of each scale, in the units of `sample_rate`.
+    pub fn frequencies(&self, sample_rate: f64) -> Vec<f64> {
+        self.scales
+            .iter()
+            .map(|&scale| self.wavelet.scale_to_frequency(scale, sample_rate))
+            .collect()
+    }
+}
+
+/// The angular frequency of the `k`th term of an FFT of length `len`, in `(-π, π]`.
+fn angular_frequency(k: usize, len: usize) -> f64 {
+    if k <= len / 2 {
+        2.0 * PI * k as f64 / len as f64
+    } else {
+        -2.0 * PI * (len - k) as f64 / len as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAVELETS: [MotherWavelet; 5] = [
+        MotherWavelet::Morlet { omega0: 6.0 },
+        MotherWavelet::MexicanHat,
+        MotherWavelet::Paul { order: 4 },
+        MotherWavelet::DerivativeOfGaussian { order: 1 },
+        MotherWavelet::DerivativeOfGaussian { order: 6 },
+    ];
+
+    #[test]
+    fn wavelets_have_unit_energy() {
+        for wavelet in WAVELETS {
+            let step = 1e-3;
+            let energy: f64 = (-20_000..20_000)
+                .map(|i| wavelet.frequency_response(i as f64 * step).norm_sqr() * step)
+                .sum();
+            assert!(
+                (energy - 1.0).abs() < 1e-6,
+                "{:?} has energy {}",
+                wavelet,
+                energy
+            );
+        }
+    }
+
+    #[test]
+    fn matches_direct_computation() {
+        let data: Vec<f64> = (0..200)
+            .map(|n| ((n * 37) % 23) as f64 - 11.0 + (n as f64 / 10.0).sin())
+            .collect();
+        // at very small scales the sampled wavelet aliases, and the two would differ slightly
+        let scales = [3.0, 4.5, 10.0];
+        let wavelet = ContinuousWavelet::transform(&data, &scales, MotherWavelet::MexicanHat);
+
+        let norm = 1.0 / gamma_half(2).sqrt();
+        let hat = |t: f64| norm * (1.0 - t * t) * (-t * t / 2.0).exp();
+        for (i, &scale) in scales.iter().enumerate() {
+            let row = wavelet.row(i).unwrap();
+            for n in [0, 57, 100, 199] {
+                let expected: f64 = data
+                    .iter()
+                    .enumerate()
+                    .map(|(m, x)| x * hat((m as f64 - n as f64) / scale) / scale.sqrt())
+                    .sum();
+                assert!(
+                    (row[n] - Complex::from(expected)).norm() < 1e-8,
+                    "Coefficient at scale {} and time {} incorrect. Expected {} but was {:?}",
+                    scale,
+                    n,
+                    expected,
+                    row[n]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn finds_the_frequency_of_a_sinusoid() {
+        let period = 16.0;
+        let data: Vec<f64> = (0..512)
+            .map(|n| (2.0 * PI * n as f64 / period).sin())
+            .collect();
+        let scales: Vec<f64> = (0..48).map(|j| 2f64.powf(j as f64 / 8.0)).collect();
+
+        for wavelet in [
+            MotherWavelet::Morlet { omega0: 6.0 },
+            MotherWavelet::Paul { order: 4 },
+        ] {
+            let transform = ContinuousWavelet::transform(&data, &scales, wavelet);
+            let power = |i: usize| -> f64 {
+                let row = transform.row(i).unwrap();
+                row[128..384].iter().map(Complex::norm_sqr).sum()
+            };
+            let best = (0..scales.len())
+                .max_by(|a, b| power(*a).total_cmp(&power(*b)))
+                .unwrap();
+
+            let frequency = transform.frequencies(1.0)[best];
+            assert!(
+                (frequency * period).log2().abs() <= 1.0 / 8.0 + 1e-9,
+                "{:?} found frequency {}, expected {}",
+                wavelet,
+                frequency,
+                1.0 / period
+            );
+        }
+    }
+
+    #[test]
+    fn converts_between_scales_and_frequencies() {
+        for wavelet in WAVELETS {
+            let scale = wavelet.frequency_to_scale(50.0, 1000.0);
+            assert!((wavelet.scale_to_frequency(scale, 1000.0) - 50.0).abs() < 1e-9);
+        }
+        assert_eq!(
+            WaveletError::InvalidScale { index: 1 },
+            ContinuousWavelet::try_transform(&[1.0, 2.0], &[1.0, 0.0], MotherWavelet::MexicanHat)
+                .unwrap_err()
+        );
+        for wavelet in [
+            MotherWavelet::Paul { order: 0 },
+            MotherWavelet::DerivativeOfGaussian { order: 0 },
+        ] {
+            assert_eq!(
+                WaveletError::InvalidOrder { order: 0 },
+                ContinuousWavelet::try_transform(&[1.0, 2.0], &[1.0], wavelet).unwrap_err()
+            );
+            assert_eq!(
+                WaveletError::InvalidOrder { order: 0 },
+                wavelet.try_frequency_response(1.0).unwrap_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,425 @@
+// The Daubechies family of orthogonal wavelets.
+use crate::filter::{Filter, FilterBank};
+use crate::wavelet::{OrthogonalDecomposition, OrthogonalWavelet};
+
+// Scaling coefficients for the Daubechies wavelets with the fewest coefficients for a given number
+// of vanishing moments. These were computed by spectral factorization of the Daubechies polynomial
+// (see `matrix.md`), choosing the roots inside the unit circle (the "extremal phase" solution).
+
+const DAUB4: [f64; 4] = [
+    0.48296291314453416,
+    0.8365163037378079,
+    0.2241438680420134,
+    -0.12940952255126037,
+];
+
+const DAUB6: [f64; 6] = [
+    0.33267055295008263,
+    0.8068915093110925,
+    0.45987750211849154,
+    -0.13501102001025458,
+    -0.08544127388202666,
+    0.03522629188570953,
+];
+
+const DAUB8: [f64; 8] = [
+    0.2303778133088965,
+    0.7148465705529157,
+    0.6308807679298589,
+    -0.027983769416859854,
+    -0.18703481171909309,
+    0.030841381835560764,
+    0.0328830116668852,
+    -0.010597401785069032,
+];
+
+const DAUB10: [f64; 10] = [
+    0.16010239797419293,
+    0.6038292697971896,
+    0.7243085284377729,
+    0.13842814590132074,
+    -0.24229488706638203,
+    -0.032244869584638375,
+    0.07757149384004572,
+    -0.006241490212798274,
+    -0.012580751999081999,
+    0.0033357252854737712,
+];
+
+const DAUB12: [f64; 12] = [
+    0.11154074335010947,
+    0.49462389039845306,
+    0.7511339080210954,
+    0.31525035170919763,
+    -0.22626469396543983,
+    -0.12976686756726194,
+    0.09750160558732304,
+    0.027522865530305727,
+    -0.03158203931748603,
+    0.0005538422011614961,
+    0.004777257510945511,
+    -0.0010773010853084796,
+];
+
+const DAUB14: [f64; 14] = [
+    0.07785205408500918,
+    0.3965393194819173,
+    0.7291320908462351,
+    0.4697822874051931,
+    -0.14390600392856498,
+    -0.22403618499387498,
+    0.07130921926683026,
+    0.08061260915108308,
+    -0.03802993693501441,
+    -0.01657454163066688,
+    0.01255099855609984,
+    0.0004295779729213665,
+    -0.0018016407040474908,
+    0.00035371379997452024,
+];
+
+const DAUB16: [f64; 16] = [
+    0.05441584224310401,
+    0.31287159091429995,
+    0.6756307362972898,
+    0.5853546836542067,
+    -0.015829105256349306,
+    -0.2840155429615469,
+    0.0004724845739132828,
+    0.12874742662047847,
+    -0.017369301001807547,
+    -0.044088253930794755,
+    0.013981027917398282,
+    0.008746094047405777,
+    -0.004870352993451574,
+    -0.00039174037337694705,
+    0.0006754494064505693,
+    -0.00011747678412476953,
+];
+
+const DAUB18: [f64; 18] = [
+    0.038077947363878345,
+    0.24383467461259034,
+    0.6048231236901112,
+    0.6572880780513005,
+    0.13319738582500756,
+    -0.2932737832791749,
+    -0.09684078322297646,
+    0.14854074933810638,
+    0.03072568147933338,
+    -0.06763282906132997,
+    0.00025094711483145197,
+    0.022361662123679096,
+    -0.004723204757751397,
+    -0.00428150368246343,
+    0.0018476468830562265,
+    0.00023038576352319597,
+    -0.0002519631889427101,
+    3.93473203162716e-05,
+];
+
+const DAUB20: [f64; 20] = [
+    0.026670057900555554,
+    0.1881768000776915,
+    0.5272011889317256,
+    0.6884590394536035,
+    0.2811723436605775,
+    -0.24984642432731538,
+    -0.19594627437737705,
+    0.12736934033579325,
+    0.09305736460357235,
+    -0.07139414716639708,
+    -0.029457536821875813,
+    0.033212674059341,
+    0.0036065535669561697,
+    -0.010733175483330575,
+    0.001395351747052901,
+    0.001992405295185056,
+    -0.0006858566949597116,
+    -0.00011646685512928545,
+    9.358867032006959e-05,
+    -1.3264202894521244e-05,
+];
+
+/// The Daubechies wavelet with 4 coefficients (DAUB4), which has 2 vanishing moments.
+///
+/// These are the coefficients derived in `matrix.md`:
+/// `h = (1 + sqrt(3), 3 + sqrt(3), 3 - sqrt(3), 1 - sqrt(3)) / (4 sqrt(2))`.
+#[derive(Debug)]
+pub struct Daub4;
+
+impl Filter for Daub4 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB4)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB4 wavelet.
+pub type Daub4Wavelet = OrthogonalWavelet<Daub4>;
+
+/// The Multi-Resolution Decomposition using the DAUB4 wavelet.
+pub type Daub4Decomposition = OrthogonalDecomposition<Daub4>;
+
+/// The Daubechies wavelet with 6 coefficients (DAUB6), which has 3 vanishing moments.
+#[derive(Debug)]
+pub struct Daub6;
+
+impl Filter for Daub6 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB6)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB6 wavelet.
+pub type Daub6Wavelet = OrthogonalWavelet<Daub6>;
+
+/// The Multi-Resolution Decomposition using the DAUB6 wavelet.
+pub type Daub6Decomposition = OrthogonalDecomposition<Daub6>;
+
+/// The Daubechies wavelet with 8 coefficients (DAUB8), which has 4 vanishing moments.
+#[derive(Debug)]
+pub struct Daub8;
+
+impl Filter for Daub8 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB8)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB8 wavelet.
+pub type Daub8Wavelet = OrthogonalWavelet<Daub8>;
+
+/// The Multi-Resolution Decomposition using the DAUB8 wavelet.
+pub type Daub8Decomposition = OrthogonalDecomposition<Daub8>;
+
+/// The Daubechies wavelet with 10 coefficients (DAUB10), which has 5 vanishing moments.
+#[derive(Debug)]
+pub struct Daub10;
+
+impl Filter for Daub10 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB10)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB10 wavelet.
+pub type Daub10Wavelet = OrthogonalWavelet<Daub10>;
+
+/// The Multi-Resolution Decomposition using the DAUB10 wavelet.
+pub type Daub10Decomposition = OrthogonalDecomposition<Daub10>;
+
+/// The Daubechies wavelet with 12 coefficients (DAUB12), which has 6 vanishing moments.
+#[derive(Debug)]
+pub struct Daub12;
+
+impl Filter for Daub12 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB12)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB12 wavelet.
+pub type Daub12Wavelet = OrthogonalWavelet<Daub12>;
+
+/// The Multi-Resolution Decomposition using the DAUB12 wavelet.
+pub type Daub12Decomposition = OrthogonalDecomposition<Daub12>;
+
+/// The Daubechies wavelet with 14 coefficients (DAUB14), which has 7 vanishing moments.
+#[derive(Debug)]
+pub struct Daub14;
+
+impl Filter for Daub14 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB14)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB14 wavelet.
+pub type Daub14Wavelet = OrthogonalWavelet<Daub14>;
+
+/// The Multi-Resolution Decomposition using the DAUB14 wavelet.
+pub type Daub14Decomposition = OrthogonalDecomposition<Daub14>;
+
+/// The Daubechies wavelet with 16 coefficients (DAUB16), which has 8 vanishing moments.
+#[derive(Debug)]
+pub struct Daub16;
+
+impl Filter for Daub16 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB16)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB16 wavelet.
+pub type Daub16Wavelet = OrthogonalWavelet<Daub16>;
+
+/// The Multi-Resolution Decomposition using the DAUB16 wavelet.
+pub type Daub16Decomposition = OrthogonalDecomposition<Daub16>;
+
+/// The Daubechies wavelet with 18 coefficients (DAUB18), which has 9 vanishing moments.
+#[derive(Debug)]
+pub struct Daub18;
+
+impl Filter for Daub18 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB18)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB18 wavelet.
+pub type Daub18Wavelet = OrthogonalWavelet<Daub18>;
+
+/// The Multi-Resolution Decomposition using the DAUB18 wavelet.
+pub type Daub18Decomposition = OrthogonalDecomposition<Daub18>;
+
+/// The Daubechies wavelet with 20 coefficients (DAUB20), which has 10 vanishing moments.
+#[derive(Debug)]
+pub struct Daub20;
+
+impl Filter for Daub20 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&DAUB20)
+    }
+}
+
+/// The Discrete Wavelet Transform using the DAUB20 wavelet.
+pub type Daub20Wavelet = OrthogonalWavelet<Daub20>;
+
+/// The Multi-Resolution Decomposition using the DAUB20 wavelet.
+pub type Daub20Decomposition = OrthogonalDecomposition<Daub20>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MRDecomposition, WaveletTransform};
+
+    fn banks() -> Vec<FilterBank> {
+        vec![
+            Daub4::filter_bank(),
+            Daub6::filter_bank(),
+            Daub8::filter_bank(),
+            Daub10::filter_bank(),
+            Daub12::filter_bank(),
+            Daub14::filter_bank(),
+            Daub16::filter_bank(),
+            Daub18::filter_bank(),
+            Daub20::filter_bank(),
+        ]
+    }
+
+    const DATA: [f64; 32] = [
+        1.0, 3.0, 5.0, 11.0, 12.0, 13.0, 0.0, 1.0, -4.0, 2.5, 7.0, 7.0, 6.0, 0.5, 3.0, 9.0, 2.0,
+        2.0, 4.0, 8.0, 16.0, 15.0, 13.0, 10.0, 6.0, 1.0, -5.0, -12.0, -4.0, 0.0, 1.0, 1.0,
+    ];
+
+    #[test]
+    fn daub4_matches_closed_form() {
+        let r3 = 3_f64.sqrt();
+        let norm = 4.0 * std::f64::consts::SQRT_2;
+        let expected = [
+            (1.0 + r3) / norm,
+            (3.0 + r3) / norm,
+            (3.0 - r3) / norm,
+            (1.0 - r3) / norm,
+        ];
+        for (pos, expected) in expected.iter().enumerate() {
+            assert!(
+                (expected - DAUB4[pos]).abs() < 1e-15,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                DAUB4[pos]
+            );
+        }
+    }
+
+    #[test]
+    fn coefficients_are_orthonormal() {
+        for bank in banks() {
+            let h = bank.scaling();
+            let sum: f64 = h.iter().sum();
+            assert!(
+                (sum - std::f64::consts::SQRT_2).abs() < 1e-14,
+                "DAUB{} is not admissible: sum was {}",
+                h.len(),
+                sum
+            );
+            for k in 0..h.len() / 2 {
+                let dot: f64 = (0..h.len() - 2 * k).map(|n| h[n] * h[n + 2 * k]).sum();
+                let expected = if k == 0 { 1.0 } else { 0.0 };
+                assert!(
+                    (dot - expected).abs() < 1e-14,
+                    "DAUB{} is not orthonormal at shift {}: {}",
+                    h.len(),
+                    k,
+                    dot
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wavelets_have_vanishing_moments() {
+        for bank in banks() {
+            let g = bank.wavelet();
+            for p in 0..g.len() as i32 / 2 {
+                let moment: f64 = g
+                    .iter()
+                    .enumerate()
+                    .map(|(n, v)| v * (n as f64).powi(p))
+                    .sum();
+                let scale: f64 = g
+                    .iter()
+                    .enumerate()
+                    .map(|(n, v)| (v * (n as f64).powi(p)).abs())
+                    .sum();
+                assert!(
+                    moment.abs() < 1e-10 * scale,
+                    "DAUB{} moment {} does not vanish: {}",
+                    g.len(),
+                    p,
+                    moment
+                );
+            }
+        }
+    }
+
+    fn assert_round_trip<W, D>()
+    where
+        W: WaveletTransform,
+        D: MRDecomposition<W>,
+    {
+        let delta = 1e-10;
+        let mut transforms = [
+            W::transform(&DATA),
+            W::transform_in_place(DATA.to_vec()),
+            D::decompose(&DATA).into(),
+        ];
+        for transform in transforms.iter_mut() {
+            for inverse in [transform.invert(), transform.invert_in_place()] {
+                for (pos, expected) in DATA.iter().enumerate() {
+                    assert!(
+                        (expected - inverse[pos]).abs() < delta,
+                        "Element at pos {} incorrect. Expected {} but was {}",
+                        pos,
+                        expected,
+                        inverse[pos]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transforms_invert() {
+        assert_round_trip::<Daub4Wavelet, Daub4Decomposition>();
+        assert_round_trip::<Daub6Wavelet, Daub6Decomposition>();
+        assert_round_trip::<Daub8Wavelet, Daub8Decomposition>();
+        assert_round_trip::<Daub10Wavelet, Daub10Decomposition>();
+        assert_round_trip::<Daub12Wavelet, Daub12Decomposition>();
+        assert_round_trip::<Daub14Wavelet, Daub14Decomposition>();
+        assert_round_trip::<Daub16Wavelet, Daub16Decomposition>();
+        assert_round_trip::<Daub18Wavelet, Daub18Decomposition>();
+        assert_round_trip::<Daub20Wavelet, Daub20Decomposition>();
+    }
+}
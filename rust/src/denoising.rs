@@ -0,0 +1,458 @@
+//! Removing noise from a signal by shrinking its wavelet coefficients.
+//!
+//! Most signals are sparse in the wavelet domain (see `notes/operations.md`), with a few large
+//! coefficients carrying almost all of the energy. White noise isn't: an orthogonal transform
+//! spreads it evenly over every coefficient. So setting the small detail coefficients to zero (and
+//! shrinking the rest) removes most of the noise while keeping most of the signal.
+//!
+//! Denoising has three parts:
+//!
+//! 1. estimating how much noise there is, usually from the finest details, which are nearly all
+//!    noise ([`Noise`]);
+//! 2. choosing the threshold for each level from that ([`Threshold`]);
+//! 3. shrinking each coefficient according to the threshold ([`Shrinkage`]).
+//!
+//! [`denoise`] does all three and inverts the transform, and [`shrink`] does them on a transform
+//! which has already been computed. Any transform with real [`DetailCoefficients`] can be
+//! denoised: the filter-bank, lifting, stationary and maximal overlap transforms. The complex
+//! coefficients of the dual-tree transform don't fit that, so it can't be.
+use crate::error::WaveletError;
+use crate::DetailCoefficients;
+use crate::WaveletTransform;
+
+/// The median of the absolute value of a standard normal variable, for estimating the standard
+/// deviation of the noise from the median absolute deviation of the coefficients.
+const MAD_TO_SIGMA: f64 = 0.6744897501960817;
+
+/// How to shrink a coefficient `x` towards zero, given a threshold `t`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Shrinkage {
+    /// Keep `x` if `|x| > t`, otherwise set it to zero.
+    Hard,
+    /// Move `x` towards zero by `t`, setting it to zero if `|x| <= t`.
+    #[default]
+    Soft,
+    /// The non-negative garrote, `x - t²/x` if `|x| > t` and zero otherwise, which is between
+    /// hard and soft: large coefficients are hardly shrunk at all.
+    Garrote,
+    /// Firm shrinkage, with a second threshold this many times the first: coefficients below `t`
+    /// are set to zero, those above the second threshold are kept, and those in between are
+    /// interpolated linearly.
+    Firm(f64),
+}
+
+impl Shrinkage {
+    /// Shrink `value` according to `threshold`.
+    pub fn apply(self, value: f64, threshold: f64) -> f64 {
+        let magnitude = value.abs();
+        if magnitude <= threshold {
+            return 0.0;
+        }
+        match self {
+            Shrinkage::Hard => value,
+            Shrinkage::Soft => value.signum() * (magnitude - threshold),
+            Shrinkage::Garrote => value - threshold * threshold / value,
+            Shrinkage::Firm(ratio) => {
+                let upper = threshold * ratio.max(1.0);
+                if magnitude > upper {
+                    value
+                } else {
+                    value.signum() * upper * (magnitude - threshold) / (upper - threshold)
+                }
+            }
+        }
+    }
+}
+
+/// How to choose the threshold at each level, from the standard deviation `σ` of the noise at
+/// that level.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Threshold {
+    /// VisuShrink: the universal threshold `σ √(2 ln N)` for `N` samples, above which pure noise
+    /// is very unlikely to reach. This removes nearly all of the noise, but tends to oversmooth.
+    #[default]
+    Universal,
+    /// SureShrink: the threshold which minimises Stein's Unbiased Risk Estimate of the mean
+    /// squared error of soft thresholding at each level. When a level is so sparse that the
+    /// estimate is unreliable, the universal threshold is used instead.
+    Sure,
+    /// BayesShrink: `σ² / σ_x` at each level, where `σ_x` is the estimated standard deviation of
+    /// the signal's coefficients, which is close to optimal for soft thresholding when the
+    /// coefficients have a generalised Gaussian distribution.
+    Bayes,
+    /// The minimax threshold of Donoho and Johnstone, which minimises the worst case risk of soft
+    /// thresholding relative to an oracle. It is smaller than the universal threshold, so keeps
+    /// more detail.
+    Minimax,
+    /// The same fixed threshold at every level.
+    Fixed(f64),
+}
+
+/// How to estimate the standard deviation of the noise.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Noise {
+    /// The noise is white, and its standard deviation is estimated from the median absolute
+    /// deviation of the finest detail coefficients.
+    #[default]
+    FinestLevel,
+    /// The noise may be coloured, so its standard deviation is estimated separately at each level
+    /// from the median absolute deviation of that level's coefficients. This gives level-dependent
+    /// thresholds even for the rules (like [`Threshold::Universal`]) which otherwise treat every
+    /// level alike.
+    LevelDependent,
+    /// The noise is white, with this standard deviation.
+    Known(f64),
+}
+
+/// The thresholds used for shrinking a transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    /// The estimated standard deviation of the noise in the coefficients at each level, starting
+    /// with the finest.
+    pub noise: Vec<f64>,
+    /// The threshold at each level, starting with the finest.
+    pub thresholds: Vec<f64>,
+}
+
+/// A denoised signal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Denoised {
+    /// The signal reconstructed from the shrunk coefficients.
+    pub signal: Vec<f64>,
+    /// The thresholds which were used.
+    pub thresholds: Thresholds,
+}
+
+/// Denoise the data with the transform `W`, estimating the noise by `noise`, choosing the
+/// thresholds by `threshold`, and shrinking the detail coefficients by `shrinkage`. The final
+/// averages are left as they are.
+pub fn denoise<W, T>(
+    data: &[T],
+    threshold: Threshold,
+    noise: Noise,
+    shrinkage: Shrinkage,
+) -> Denoised
+where
+    W: WaveletTransform + DetailCoefficients,
+    T: Into<f64> + Copy,
+{
+    let mut transform = W::transform(data);
+    let thresholds = shrink(&mut transform, threshold, noise, shrinkage);
+    Denoised {
+        signal: transform.invert_in_place(),
+        thresholds,
+    }
+}
+
+/// Denoise the data, as in [`denoise`], or return an error if the data can't be transformed.
+pub fn try_denoise<W, T>(
+    data: &[T],
+    threshold: Threshold,
+    noise: Noise,
+    shrinkage: Shrinkage,
+) -> Result<Denoised, WaveletError>
+where
+    W: WaveletTransform + DetailCoefficients,
+    T: Into<f64> + Copy,
+{
+    let mut transform = W::try_transform(data)?;
+    let thresholds = shrink(&mut transform, threshold, noise, shrinkage);
+    Ok(Denoised {
+        signal: transform.invert_in_place(),
+        thresholds,
+    })
+}
+
+/// Shrink the detail coefficients of a transform in place, returning the thresholds used.
+pub fn shrink<W>(
+    transform: &mut W,
+    threshold: Threshold,
+    noise: Noise,
+    shrinkage: Shrinkage,
+) -> Thresholds
+where
+    W: DetailCoefficients,
+{
+    let noise = estimate_noise(transform, noise);
+    let thresholds: Vec<f64> = noise
+        .iter()
+        .enumerate()
+        .map(|(j, &sigma)| {
+            let details = transform.detail_coefficients(j + 1).unwrap_or(&[]);
+            choose_threshold(threshold, details, sigma, transform.signal_len())
+        })
+        .collect();
+
+    for (j, &t) in thresholds.iter().enumerate() {
+        if let Some(details) = transform.detail_coefficients_mut(j + 1) {
+            for d in details.iter_mut() {
+                *d = shrinkage.apply(*d, t);
+            }
+        }
+    }
+    Thresholds { noise, thresholds }
+}
+
+/// Estimate the standard deviation of the noise in the detail coefficients at each level of a
+/// transform, starting with the finest.
+pub fn estimate_noise<W>(transform: &W, noise: Noise) -> Vec<f64>
+where
+    W: DetailCoefficients,
+{
+    let levels = 1..=transform.detail_levels();
+    let mad =
+        |level| median_absolute(transform.detail_coefficients(level).unwrap_or(&[])) / MAD_TO_SIGMA;
+    match noise {
+        Noise::LevelDependent => levels.map(mad).collect(),
+        Noise::FinestLevel | Noise::Known(_) => {
+            let sigma = match noise {
+                Noise::Known(sigma) => sigma,
+                _ if transform.detail_levels() == 0 => 0.0,
+                _ => mad(1) / transform.noise_gain(1),
+            };
+            levels
+                .map(|level| sigma * transform.noise_gain(level))
+                .collect()
+        }
+    }
+}
+
+fn choose_threshold(threshold: Threshold, details: &[f64], sigma: f64, len: usize) -> f64 {
+    let universal = |n: usize| sigma * (2.0 * (n.max(1) as f64).ln()).sqrt();
+    match threshold {
+        Threshold::Universal => universal(len),
+        Threshold::Minimax if len > 32 => sigma * (0.3936 + 0.1829 * (len as f64).log2()),
+        Threshold::Minimax => 0.0,
+        Threshold::Fixed(t) => t,
+        Threshold::Bayes => {
+            let variance = details.iter().map(|d| d * d).sum::<f64>() / details.len().max(1) as f64;
+            let signal = (variance - sigma * sigma).max(0.0).sqrt();
+            if signal > 0.0 {
+                sigma * sigma / signal
+            } else {
+                // it's all noise
+                details.iter().fold(0.0, |m, d| m.max(d.abs()))
+            }
+        }
+        Threshold::Sure if sigma <= 0.0 || details.is_empty() => 0.0,
+        Threshold::Sure => {
+            let n = details.len() as f64;
+            let universal = universal(details.len()) / sigma;
+            let mut squares: Vec<f64> = details.iter().map(|d| (d / sigma).powi(2)).collect();
+            squares.sort_by(f64::total_cmp);
+
+            // too sparse for SURE to find anything
+            let energy = (squares.iter().sum::<f64>() - n) / n;
+            if energy <= n.log2().powf(1.5) / n.sqrt() {
+                return universal * sigma;
+            }
+
+            // the risk of thresholding at each |x|, Σ min(x², t²) - 2 #{|x| <= t} + n
+            let mut below = 0.0;
+            let (best, _) = squares.iter().enumerate().fold(
+                (0.0, f64::INFINITY),
+                |(best, lowest), (k, &t2)| {
+                    below += t2;
+                    let risk = n - 2.0 * (k + 1) as f64 + below + (n - (k + 1) as f64) * t2;
+                    if risk < lowest {
+                        (t2.sqrt(), risk)
+                    } else {
+                        (best, lowest)
+                    }
+                },
+            );
+            best.min(universal) * sigma
+        }
+    }
+}
+
+fn median_absolute(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut magnitudes: Vec<f64> = values.iter().map(|v| v.abs()).collect();
+    magnitudes.sort_by(f64::total_cmp);
+    let mid = magnitudes.len() / 2;
+    if magnitudes.len().is_multiple_of(2) {
+        (magnitudes[mid - 1] + magnitudes[mid]) / 2.0
+    } else {
+        magnitudes[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biorthogonal::Bior2_2;
+    use crate::daubechies::Daub8;
+    use crate::filter::Filter;
+    use crate::haar::HaarWavelet;
+    use crate::modwt::MaximalOverlapWavelet;
+    use crate::stationary::StationaryWavelet;
+    use crate::wavelet::FilterWavelet;
+
+    /// Gaussian noise with standard deviation `sigma`, by the Box-Muller transform of a linear
+    /// congruential generator.
+    fn noise(len: usize, sigma: f64) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut uniform = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((state >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        };
+        (0..len)
+            .map(|_| {
+                let (u, v) = (uniform(), uniform());
+                sigma * (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+            })
+            .collect()
+    }
+
+    /// A piecewise constant signal.
+    fn blocks(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| match i * 8 / len {
+                0 | 5 => 0.0,
+                1 => 4.0,
+                2 => -2.0,
+                3 | 6 => 3.0,
+                _ => 1.0,
+            })
+            .collect()
+    }
+
+    fn mean_squared_error(a: &[f64], b: &[f64]) -> f64 {
+        a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>() / a.len() as f64
+    }
+
+    #[test]
+    fn shrinks_coefficients() {
+        assert_eq!(0.0, Shrinkage::Hard.apply(0.9, 1.0));
+        assert_eq!(-3.0, Shrinkage::Hard.apply(-3.0, 1.0));
+        assert_eq!(-2.0, Shrinkage::Soft.apply(-3.0, 1.0));
+        assert_eq!(0.0, Shrinkage::Soft.apply(1.0, 1.0));
+        assert_eq!(1.5, Shrinkage::Garrote.apply(2.0, 1.0));
+        assert_eq!(0.0, Shrinkage::Firm(2.0).apply(-0.5, 1.0));
+        assert_eq!(1.0, Shrinkage::Firm(2.0).apply(1.5, 1.0));
+        assert_eq!(-2.5, Shrinkage::Firm(2.0).apply(-2.5, 1.0));
+    }
+
+    #[test]
+    fn estimates_white_noise() {
+        let data = noise(4096, 0.5);
+        let dwt = HaarWavelet::transform(&data);
+        let sigma = estimate_noise(&dwt, Noise::FinestLevel);
+        assert!((sigma[0] - 0.5).abs() < 0.03, "σ = {}", sigma[0]);
+
+        // the MODWT's coefficients shrink by √2 with each level
+        let modwt = MaximalOverlapWavelet::<Daub8>::try_transform_with(&data, 3).unwrap();
+        let sigma = estimate_noise(&modwt, Noise::FinestLevel);
+        for (j, s) in sigma.iter().enumerate() {
+            let expected = 0.5 / 2f64.sqrt().powi(j as i32 + 1);
+            assert!(
+                (s - expected).abs() < 0.03 * expected,
+                "σ_{} = {}",
+                j + 1,
+                s
+            );
+        }
+
+        // biorthogonal filters don't preserve the noise level either
+        let bior = FilterWavelet::<Bior2_2>::transform(&data);
+        let sigma = estimate_noise(&bior, Noise::FinestLevel);
+        let gain = Bior2_2::filter_bank().analysis_gain(1);
+        assert!((gain - 1.0).abs() > 0.1);
+        assert!(
+            (sigma[0] / gain - 0.5).abs() < 0.03,
+            "σ = {}",
+            sigma[0] / gain
+        );
+    }
+
+    #[test]
+    fn removes_noise() {
+        let clean = blocks(1024);
+        let noisy: Vec<f64> = clean
+            .iter()
+            .zip(noise(1024, 0.5))
+            .map(|(c, n)| c + n)
+            .collect();
+        let before = mean_squared_error(&clean, &noisy);
+
+        for threshold in [
+            Threshold::Universal,
+            Threshold::Sure,
+            Threshold::Bayes,
+            Threshold::Minimax,
+        ] {
+            for shrinkage in [
+                Shrinkage::Hard,
+                Shrinkage::Soft,
+                Shrinkage::Garrote,
+                Shrinkage::Firm(2.0),
+            ] {
+                let denoised =
+                    denoise::<HaarWavelet, _>(&noisy, threshold, Noise::FinestLevel, shrinkage);
+                let after = mean_squared_error(&clean, &denoised.signal);
+                assert!(
+                    after < before / 3.0,
+                    "{:?} {:?} only reduced the error from {} to {}",
+                    threshold,
+                    shrinkage,
+                    before,
+                    after
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn chooses_thresholds() {
+        let noisy: Vec<f64> = blocks(1024)
+            .iter()
+            .zip(noise(1024, 0.5))
+            .map(|(c, n)| c + n)
+            .collect();
+        let thresholds = |threshold, noise| {
+            let mut swt = StationaryWavelet::<Daub8>::try_transform_with(&noisy, 4).unwrap();
+            shrink(&mut swt, threshold, noise, Shrinkage::Soft)
+        };
+
+        let universal = thresholds(Threshold::Universal, Noise::Known(0.5));
+        let expected = 0.5 * (2.0 * 1024f64.ln()).sqrt();
+        assert_eq!(
+            vec![0.5; 4],
+            universal
+                .noise
+                .iter()
+                .map(|s| (s * 1e9).round() / 1e9)
+                .collect::<Vec<_>>()
+        );
+        for t in &universal.thresholds {
+            assert!((t - expected).abs() < 1e-9);
+        }
+
+        // the other rules adapt to each level, but never go above the universal threshold
+        let minimax = thresholds(Threshold::Minimax, Noise::Known(0.5));
+        assert!(minimax.thresholds[0] < expected);
+        let sure = thresholds(Threshold::Sure, Noise::FinestLevel);
+        assert!(sure
+            .thresholds
+            .iter()
+            .all(|t| *t <= 0.6 * (2.0 * 1024f64.ln()).sqrt()));
+        let bayes = thresholds(Threshold::Bayes, Noise::LevelDependent);
+        assert_eq!(4, bayes.thresholds.len());
+        assert!(bayes.thresholds.iter().all(|t| t.is_finite() && *t > 0.0));
+
+        assert_eq!(
+            Err(WaveletError::NonFiniteInput { index: 2 }),
+            try_denoise::<HaarWavelet, _>(
+                &[1.0, 2.0, f64::NAN, 4.0],
+                Threshold::Universal,
+                Noise::FinestLevel,
+                Shrinkage::Soft
+            )
+        );
+    }
+}
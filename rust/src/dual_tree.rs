@@ -0,0 +1,779 @@
+//! The Dual-Tree Complex Wavelet Transform.
+//!
+//! The decimated transform is shift-variant, because each level aliases: the differences at a
+//! level pick up some of the energy of frequencies outside their band, and which ones depends on
+//! exactly where the samples fall. The stationary transform (see [`crate::stationary`]) fixes that
+//! by not decimating at all, at the cost of `N` coefficients per level. Kingsbury's dual-tree
+//! transform gets most of the benefit for a redundancy of just 2 (or 4 in 2-D).
+//!
+//! It runs two ordinary decimated transforms side by side, with filters chosen so that the
+//! wavelet of the second tree is (very nearly) the Hilbert transform of the first. Taking the
+//! first tree's differences as the real part and the second's as the imaginary part then gives
+//! the coefficients of an analytic, complex wavelet: their magnitude varies smoothly as the signal
+//! shifts, where the real coefficients oscillate, and their phase encodes the position of features
+//! within each coefficient's support.
+//!
+//! The first level uses the same (near-symmetric 5/7-tap) biorthogonal filters in both trees, with
+//! the second tree offset by a single sample. The later levels use Kingsbury's 10-tap _Q-shift_
+//! filters: an orthogonal filter whose group delay is a quarter of a sample, and its time-reverse,
+//! whose delay is three quarters, so that the trees stay half a sample apart at every level.
+//!
+//! In 2-D, the rows and columns can each be transformed with either tree, giving four trees. The
+//! differences of each subband of those four combine into a pair of complex subbands, one
+//! responding to features at `+θ` and one to features at `-θ`, for six directions in all: ±15°,
+//! ±45° and ±75° (see [`Direction`]).
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::boundary::BoundaryMode;
+use crate::complex::Complex;
+use crate::error::{self, WaveletError};
+use crate::filter::FilterBank;
+use crate::two_dimensional::{self, Axis, Band, Grid, Orientation};
+use crate::wavelet;
+
+/// The near-symmetric 5/7-tap analysis filter (`near_sym_a`) used at the first level.
+const NEAR_SYM_A_DUAL: [f64; 5] = [
+    -0.07071067811865475,
+    0.3535533905932738,
+    0.848528137423857,
+    0.3535533905932738,
+    -0.07071067811865475,
+];
+
+/// The near-symmetric 5/7-tap reconstruction filter (`near_sym_a`) used at the first level.
+const NEAR_SYM_A: [f64; 7] = [
+    -0.01515228816828316,
+    -0.07576144084141581,
+    0.3687056787615569,
+    0.8586296628693791,
+    0.3687056787615569,
+    -0.07576144084141581,
+    -0.01515228816828316,
+];
+
+/// Kingsbury's 10-tap Q-shift filter (`qshift_a`), with a group delay of about a quarter sample.
+const QSHIFT_A: [f64; 10] = [
+    0.0511304052838317,
+    -0.0139753702468888,
+    -0.109836051665971,
+    0.263839561058938,
+    0.766628467793037,
+    0.563655710127052,
+    0.000873622695217097,
+    -0.100231219507476,
+    -0.00168968127252815,
+    -0.00618188189211644,
+];
+
+/// The filters of one of the two trees.
+struct Tree {
+    first: FilterBank,
+    rest: FilterBank,
+    /// Whether this tree is offset by a sample at the first level.
+    shifted: bool,
+}
+
+impl Tree {
+    /// The real tree (`false`) or the imaginary tree (`true`).
+    fn new(imaginary: bool) -> Tree {
+        let mut qshift = QSHIFT_A.to_vec();
+        if imaginary {
+            qshift.reverse();
+        }
+        Tree {
+            first: FilterBank::biorthogonal(&NEAR_SYM_A, &NEAR_SYM_A_DUAL),
+            rest: FilterBank::orthogonal(&qshift),
+            shifted: imaginary,
+        }
+    }
+
+    fn bank(&self, level: usize) -> &FilterBank {
+        if level == 0 {
+            &self.first
+        } else {
+            &self.rest
+        }
+    }
+
+    /// Transform the data, leaving it in the layout `[avg | d^J | ... | d^1]`.
+    ///
+    /// As in [`crate::wavelet::FilterWavelet`], a level with an odd number of samples repeats the
+    /// last one, so there can be more coefficients than samples.
+    fn forward(&self, data: &mut Vec<f64>, levels: usize) {
+        if self.shifted && !data.is_empty() {
+            data.rotate_left(1);
+        }
+        let lengths = wavelet::level_lengths(&self.first, data.len(), PERIODIZATION, levels);
+        for (level, &len) in lengths[..levels].iter().enumerate() {
+            let transform = wavelet::dwt(self.bank(level), &data[..len], PERIODIZATION, 1);
+            data.splice(..len, transform);
+        }
+    }
+
+    /// Undo [`Tree::forward`] of `len` samples.
+    fn inverse(&self, data: &mut Vec<f64>, len: usize, levels: usize) {
+        let lengths = wavelet::level_lengths(&self.first, len, PERIODIZATION, levels);
+        for level in (0..levels).rev() {
+            let size = 2 * lengths[level + 1];
+            let inverse = wavelet::inverse_dwt(
+                self.bank(level),
+                &data[..size],
+                lengths[level],
+                PERIODIZATION,
+                1,
+            );
+            data.splice(..size, inverse);
+        }
+        if self.shifted && !data.is_empty() {
+            data.rotate_right(1);
+        }
+    }
+
+    /// The bands of a transform of `len` samples by `levels` levels.
+    fn axis(&self, len: usize, levels: usize) -> Axis {
+        Axis::new(&self.first, len, levels)
+    }
+}
+
+const PERIODIZATION: BoundaryMode = BoundaryMode::Periodization;
+
+/// The most levels of the transform that `len` samples can support.
+fn max_levels(len: usize) -> usize {
+    wavelet::max_levels(&Tree::new(false).first, len, PERIODIZATION)
+}
+
+/// Check that `levels` levels can be computed, where `max` is the most there can be.
+fn check_levels(levels: usize, max: usize) -> Result<(), WaveletError> {
+    if levels > max {
+        return Err(WaveletError::LevelTooDeep {
+            requested: levels,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// `(re + i·im)/√2` for each pair.
+fn combine(re: &[f64], im: &[f64]) -> Vec<Complex> {
+    re.iter()
+        .zip(im)
+        .map(|(a, b)| Complex::new(*a, *b) * FRAC_1_SQRT_2)
+        .collect()
+}
+
+/// The 1-D Dual-Tree Complex Wavelet Transform.
+///
+/// Each level halves the data (rounding up), so data of any length can be transformed, and the
+/// coefficients are laid out as [`crate::wavelet::FilterWavelet`] lays them out.
+#[derive(Debug)]
+pub struct DualTreeWavelet {
+    real: Vec<f64>,
+    imaginary: Vec<f64>,
+    len: usize,
+    levels: usize,
+}
+
+impl DualTreeWavelet {
+    /// Perform `levels` levels of the transform, or return an error if the data isn't finite, or
+    /// can't support that many levels.
+    pub fn try_transform_with<T>(data: &[T], levels: usize) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        check_levels(levels, Self::max_levels(data.len()))?;
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Ok(Self::transform_levels(floats, levels))
+    }
+
+    /// The most levels of the transform that `len` samples can support.
+    ///
+    /// As with [`crate::boundary::BoundaryMode::Periodization`], that's enough levels to get down
+    /// to a single average.
+    pub fn max_levels(len: usize) -> usize {
+        max_levels(len)
+    }
+
+    fn transform_levels(mut real: Vec<f64>, levels: usize) -> Self {
+        let len = real.len();
+        let mut imaginary = real.clone();
+        Tree::new(false).forward(&mut real, levels);
+        Tree::new(true).forward(&mut imaginary, levels);
+        DualTreeWavelet {
+            real,
+            imaginary,
+            len,
+            levels,
+        }
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// The length of the data.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the data was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The complex wavelet coefficients at `level`, where level 1 is the finest, or `None` if
+    /// there's no such level.
+    ///
+    /// Each is `(a + ib)/√2`, where `a` and `b` are the differences of the real and imaginary
+    /// trees.
+    pub fn coefficients(&self, level: usize) -> Option<Vec<Complex>> {
+        let (start, len) = self.band(Band::Detail(level))?;
+        let range = start..start + len;
+        Some(combine(&self.real[range.clone()], &self.imaginary[range]))
+    }
+
+    /// The magnitudes of the complex wavelet coefficients at `level`, or `None` if there's no
+    /// such level.
+    pub fn magnitudes(&self, level: usize) -> Option<Vec<f64>> {
+        Some(
+            self.coefficients(level)?
+                .iter()
+                .map(Complex::norm)
+                .collect(),
+        )
+    }
+
+    /// The phases of the complex wavelet coefficients at `level`, or `None` if there's no such
+    /// level.
+    pub fn phases(&self, level: usize) -> Option<Vec<f64>> {
+        Some(self.coefficients(level)?.iter().map(Complex::arg).collect())
+    }
+
+    /// The averages at the coarsest level, combined into complex numbers in the same way as the
+    /// wavelet coefficients.
+    pub fn scaling_coefficients(&self) -> Vec<Complex> {
+        let (_, len) = self.band(Band::Average).unwrap_or_default();
+        combine(&self.real[..len], &self.imaginary[..len])
+    }
+
+    /// The position and length of a band in the layout `[avg | d^J | ... | d^1]`.
+    fn band(&self, band: Band) -> Option<(usize, usize)> {
+        Tree::new(false).axis(self.len, self.levels).band(band)
+    }
+}
+
+impl super::WaveletTransform for DualTreeWavelet {
+    fn transform<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect())
+    }
+
+    fn transform_in_place(data: Vec<f64>) -> Self {
+        let levels = Self::max_levels(data.len());
+        Self::transform_levels(data, levels)
+    }
+
+    fn invert(&self) -> Vec<f64> {
+        let mut real = self.real.clone();
+        let mut imaginary = self.imaginary.clone();
+        Tree::new(false).inverse(&mut real, self.len, self.levels);
+        Tree::new(true).inverse(&mut imaginary, self.len, self.levels);
+        average(real, &[imaginary])
+    }
+
+    fn invert_in_place(&mut self) -> Vec<f64> {
+        let mut real = std::mem::take(&mut self.real);
+        let mut imaginary = std::mem::take(&mut self.imaginary);
+        Tree::new(false).inverse(&mut real, self.len, self.levels);
+        Tree::new(true).inverse(&mut imaginary, self.len, self.levels);
+        average(real, &[imaginary])
+    }
+}
+
+/// The average of the reconstructions of each tree, which are all the same (up to floating
+/// point errors).
+fn average(mut first: Vec<f64>, rest: &[Vec<f64>]) -> Vec<f64> {
+    let count = (rest.len() + 1) as f64;
+    for (pos, v) in first.iter_mut().enumerate() {
+        *v = (*v + rest.iter().map(|r| r[pos]).sum::<f64>()) / count;
+    }
+    first
+}
+
+/// The six directions of the 2-D Dual-Tree Complex Wavelet Transform's subbands.
+///
+/// Each is the angle (anticlockwise from the horizontal, with rows running down the image) of the
+/// edges and lines which the subband responds to most strongly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Nearly horizontal, rising to the right.
+    Plus15,
+    /// Diagonal, rising to the right.
+    Plus45,
+    /// Nearly vertical, rising to the right.
+    Plus75,
+    /// Nearly horizontal, falling to the right.
+    Minus15,
+    /// Diagonal, falling to the right.
+    Minus45,
+    /// Nearly vertical, falling to the right.
+    Minus75,
+}
+
+impl Direction {
+    /// Every direction, in order of angle.
+    pub const ALL: [Direction; 6] = [
+        Direction::Minus75,
+        Direction::Minus45,
+        Direction::Minus15,
+        Direction::Plus15,
+        Direction::Plus45,
+        Direction::Plus75,
+    ];
+
+    /// The angle in degrees.
+    pub fn degrees(&self) -> f64 {
+        match self {
+            Direction::Plus15 => 15.0,
+            Direction::Plus45 => 45.0,
+            Direction::Plus75 => 75.0,
+            Direction::Minus15 => -15.0,
+            Direction::Minus45 => -45.0,
+            Direction::Minus75 => -75.0,
+        }
+    }
+
+    /// The subband of the real transforms which this direction is formed from, and whether it
+    /// uses the product of the row and column wavelets (rather than the product with the
+    /// conjugate of the column wavelet).
+    fn subband(&self) -> (Orientation, bool) {
+        match self {
+            Direction::Plus15 => (Orientation::LH, true),
+            Direction::Minus15 => (Orientation::LH, false),
+            Direction::Plus45 => (Orientation::HH, true),
+            Direction::Minus45 => (Orientation::HH, false),
+            Direction::Plus75 => (Orientation::HL, true),
+            Direction::Minus75 => (Orientation::HL, false),
+        }
+    }
+}
+
+/// The 2-D Dual-Tree Complex Wavelet Transform.
+///
+/// This holds four non-standard 2-D transforms (see [`crate::two_dimensional`]) of the data, one
+/// for each combination of the real or imaginary tree along the rows and along the columns. Each
+/// level halves both dimensions (rounding up), so data of any dimensions can be transformed.
+#[derive(Debug)]
+pub struct DualTreeWavelet2D {
+    /// The transforms using the (rows, columns) trees (real, real), (real, imaginary),
+    /// (imaginary, real) and (imaginary, imaginary).
+    trees: [Vec<f64>; 4],
+    width: usize,
+    height: usize,
+    levels: usize,
+}
+
+impl DualTreeWavelet2D {
+    /// Perform as many levels of the transform as the dimensions allow.
+    ///
+    /// This panics if the data doesn't hold `width * height` samples.
+    pub fn transform<T>(data: &[T], width: usize, height: usize) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let levels = Self::max_levels(width, height);
+        Self::try_transform(data, width, height, levels).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Perform `levels` levels of the transform, or return an error if the data doesn't hold
+    /// `width * height` finite samples, or the dimensions can't support that many levels.
+    pub fn try_transform<T>(
+        data: &[T],
+        width: usize,
+        height: usize,
+        levels: usize,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let floats: Vec<f64> = data.iter().map(|v| (*v).into()).collect();
+        two_dimensional::check_dimensions(&floats, width, height)?;
+        check_levels(levels, Self::max_levels(width, height))?;
+
+        let trees =
+            [(false, false), (false, true), (true, false), (true, true)].map(|(rows, columns)| {
+                forward_2d(floats.clone(), width, height, levels, rows, columns)
+            });
+        Ok(DualTreeWavelet2D {
+            trees,
+            width,
+            height,
+            levels,
+        })
+    }
+
+    /// The most levels of the transform that data of these dimensions can support.
+    ///
+    /// This is the number of levels of the 1-D transform that the shorter axis supports.
+    pub fn max_levels(width: usize, height: usize) -> usize {
+        max_levels(width).min(max_levels(height))
+    }
+
+    /// Invert the transform, leaving this instance unaffected.
+    pub fn invert(&self) -> Vec<f64> {
+        let trees = self.trees.clone();
+        self.invert_trees(trees)
+    }
+
+    /// Invert the transform in place, moving the underlying memory into the return value.
+    pub fn invert_in_place(&mut self) -> Vec<f64> {
+        let trees = std::mem::take(&mut self.trees);
+        self.invert_trees(trees)
+    }
+
+    fn invert_trees(&self, mut trees: [Vec<f64>; 4]) -> Vec<f64> {
+        let combinations = [(false, false), (false, true), (true, false), (true, true)];
+        for (tree, (rows, columns)) in trees.iter_mut().zip(combinations) {
+            inverse_2d(tree, self.width, self.height, self.levels, rows, columns);
+        }
+        let [first, rest @ ..] = trees;
+        average(first, &rest)
+    }
+
+    /// The width of the data.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the data.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// The width and height of the subbands at `level`, where level 1 is the finest.
+    pub fn subband_size(&self, level: usize) -> (usize, usize) {
+        let tree = Tree::new(false);
+        (
+            tree.axis(self.width, level).lengths[level],
+            tree.axis(self.height, level).lengths[level],
+        )
+    }
+
+    /// The complex coefficients of the subband at `level` (where level 1 is the finest) in the
+    /// given direction, in row-major order, or `None` if there's no such level.
+    pub fn subband(&self, level: usize, direction: Direction) -> Option<Vec<Complex>> {
+        let (orientation, product) = direction.subband();
+        let tree = Tree::new(false);
+        let horizontal = tree.axis(self.width, self.levels);
+        let vertical = tree.axis(self.height, self.levels);
+        let (x, y, width, height) =
+            two_dimensional::subband_bounds(&horizontal, &vertical, level, orientation)?;
+        let stride = horizontal.size();
+        let region = |tree: &[f64]| -> Vec<f64> {
+            (y..y + height)
+                .flat_map(|row| {
+                    let start = row * stride + x;
+                    tree[start..start + width].iter().copied()
+                })
+                .collect()
+        };
+        let [aa, ab, ba, bb] = &self.trees;
+        let (aa, ab, ba, bb) = (region(aa), region(ab), region(ba), region(bb));
+        // the product of the row wavelet a_r + i·b_r with the column wavelet a_c ± i·b_c
+        Some(
+            (0..aa.len())
+                .map(|i| {
+                    let z = if product {
+                        Complex::new(aa[i] - bb[i], ab[i] + ba[i])
+                    } else {
+                        Complex::new(aa[i] + bb[i], ba[i] - ab[i])
+                    };
+                    z * FRAC_1_SQRT_2
+                })
+                .collect(),
+        )
+    }
+
+    /// The magnitudes of the subband at `level` in the given direction, or `None` if there's no
+    /// such level.
+    pub fn magnitudes(&self, level: usize, direction: Direction) -> Option<Vec<f64>> {
+        Some(
+            self.subband(level, direction)?
+                .iter()
+                .map(Complex::norm)
+                .collect(),
+        )
+    }
+
+    /// The phases of the subband at `level` in the given direction, or `None` if there's no such
+    /// level.
+    pub fn phases(&self, level: usize, direction: Direction) -> Option<Vec<f64>> {
+        Some(
+            self.subband(level, direction)?
+                .iter()
+                .map(Complex::arg)
+                .collect(),
+        )
+    }
+}
+
+/// Transform a 2-D grid with the given trees along the rows and the columns, in the layout of
+/// the non-standard [`crate::two_dimensional::FilterWavelet2D`].
+fn forward_2d(
+    mut data: Vec<f64>,
+    width: usize,
+    height: usize,
+    levels: usize,
+    imaginary_rows: bool,
+    imaginary_columns: bool,
+) -> Vec<f64> {
+    let (rows, columns) = (Tree::new(imaginary_rows), Tree::new(imaginary_columns));
+    if levels > 0 {
+        shift_2d(&mut data, width, rows.shifted, columns.shifted, true);
+    }
+    let (horizontal, vertical) = (rows.axis(width, levels), columns.axis(height, levels));
+    let stride = horizontal.size();
+    let mut coefficients = two_dimensional::spread(data, width, height, stride, vertical.size());
+    Grid::new(&mut coefficients, stride).transform_levels(
+        &horizontal,
+        &vertical,
+        levels,
+        |level, line| wavelet::dwt_in_place(rows.bank(level - 1), line, 1),
+        |level, line| wavelet::dwt_in_place(columns.bank(level - 1), line, 1),
+    );
+    coefficients
+}
+
+/// Undo [`forward_2d`].
+fn inverse_2d(
+    data: &mut Vec<f64>,
+    width: usize,
+    height: usize,
+    levels: usize,
+    imaginary_rows: bool,
+    imaginary_columns: bool,
+) {
+    let (rows, columns) = (Tree::new(imaginary_rows), Tree::new(imaginary_columns));
+    let (horizontal, vertical) = (rows.axis(width, levels), columns.axis(height, levels));
+    let stride = horizontal.size();
+    Grid::new(data, stride).invert_levels(
+        &horizontal,
+        &vertical,
+        levels,
+        |level, len, line| wavelet::inverse_dwt_in_place(rows.bank(level - 1), line, len, 1),
+        |level, len, line| wavelet::inverse_dwt_in_place(columns.bank(level - 1), line, len, 1),
+    );
+    two_dimensional::compact(data, width, height, stride);
+    if levels > 0 {
+        shift_2d(data, width, rows.shifted, columns.shifted, false);
+    }
+}
+
+/// Shift the grid a sample left along the rows and/or up along the columns (or back again), for
+/// the first level of the imaginary trees.
+fn shift_2d(data: &mut [f64], width: usize, rows: bool, columns: bool, forward: bool) {
+    if data.is_empty() {
+        return;
+    }
+    if rows {
+        for row in data.chunks_mut(width) {
+            if forward {
+                row.rotate_left(1);
+            } else {
+                row.rotate_right(1);
+            }
+        }
+    }
+    if columns {
+        if forward {
+            data.rotate_left(width);
+        } else {
+            data.rotate_right(width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft;
+    use crate::test_util::assert_close;
+    use crate::WaveletTransform;
+
+    fn signal(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| ((i * 37) % 23) as f64 - 11.0 + (i as f64 / 5.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn filters_are_perfect_reconstruction() {
+        let tree = Tree::new(false);
+        assert!(tree.first.is_biorthogonal(1e-12));
+        assert!(tree.rest.is_orthonormal(1e-12));
+        assert!(Tree::new(true).rest.is_orthonormal(1e-12));
+    }
+
+    #[test]
+    fn inverts() {
+        for (len, levels) in [(1, 0), (2, 1), (7, 3), (8, 3), (13, 4), (24, 5), (64, 6)] {
+            let data = signal(len);
+            let wavelet = DualTreeWavelet::transform(&data);
+            assert_eq!(levels, wavelet.levels());
+            assert_eq!(len, wavelet.len());
+            assert_close(&data, &wavelet.invert(), "1-D");
+        }
+
+        let data = signal(16 * 8);
+        let mut wavelet = DualTreeWavelet2D::transform(&data, 16, 8);
+        assert_eq!(3, wavelet.levels());
+        assert_close(&data, &wavelet.invert_in_place(), "2-D");
+
+        let data = signal(13 * 7);
+        let mut wavelet = DualTreeWavelet2D::transform(&data, 13, 7);
+        assert_eq!(3, wavelet.levels());
+        assert_close(&data, &wavelet.invert_in_place(), "Odd 2-D");
+    }
+
+    #[test]
+    fn lays_out_odd_lengths_like_the_real_transform() {
+        // 13 samples have levels of 7, 4, 2 and 1 coefficients
+        let wavelet = DualTreeWavelet::transform(&signal(13));
+        let sizes: Vec<usize> = (1..=4)
+            .map(|level| wavelet.coefficients(level).unwrap().len())
+            .collect();
+        assert_eq!(vec![7, 4, 2, 1], sizes);
+        assert_eq!(1, wavelet.scaling_coefficients().len());
+        assert!(wavelet.coefficients(5).is_none());
+
+        let wavelet = DualTreeWavelet2D::transform(&signal(13 * 7), 13, 7);
+        for (level, size) in [(1, (7, 4)), (2, (4, 2)), (3, (2, 1))] {
+            assert_eq!(size, wavelet.subband_size(level));
+            for direction in Direction::ALL {
+                let subband = wavelet.subband(level, direction).unwrap();
+                assert_eq!(size.0 * size.1, subband.len());
+            }
+        }
+    }
+
+    #[test]
+    fn wavelets_are_analytic() {
+        // the response to an impulse at each position gives the complex wavelet at each level,
+        // which should have (almost) no energy at negative frequencies
+        let len = 256;
+        for level in 2..=4 {
+            let k = (len >> level) / 2;
+            let mut kernel: Vec<Complex> = (0..len)
+                .map(|p| {
+                    let mut impulse = vec![0.0; len];
+                    impulse[p] = 1.0;
+                    let wavelet = DualTreeWavelet::try_transform_with(&impulse, level).unwrap();
+                    wavelet.coefficients(level).unwrap()[k]
+                })
+                .collect();
+            fft::fft(&mut kernel);
+
+            let energy = |range: std::ops::Range<usize>| -> f64 {
+                kernel[range].iter().map(Complex::norm_sqr).sum()
+            };
+            let (positive, negative) = (energy(1..len / 2), energy(len / 2 + 1..len));
+            let leakage = positive.min(negative) / (positive + negative);
+            assert!(
+                leakage < 0.01,
+                "Level {} leaks {} of its energy",
+                level,
+                leakage
+            );
+        }
+    }
+
+    #[test]
+    fn magnitudes_are_nearly_shift_invariant() {
+        // the energy of a level varies far less with the position of an impulse than that of a
+        // real transform does
+        let len = 64;
+        let spread = |energies: Vec<f64>| {
+            let max = energies.iter().cloned().fold(f64::MIN, f64::max);
+            let min = energies.iter().cloned().fold(f64::MAX, f64::min);
+            max / min
+        };
+        let impulse = |p: usize| {
+            let mut data = vec![0.0; len];
+            data[20 + p] = 1.0;
+            data
+        };
+        let complex = spread(
+            (0..16)
+                .map(|p| {
+                    let wavelet = DualTreeWavelet::try_transform_with(&impulse(p), 3).unwrap();
+                    wavelet.magnitudes(3).unwrap().iter().map(|m| m * m).sum()
+                })
+                .collect(),
+        );
+        let real = spread(
+            (0..16)
+                .map(|p| {
+                    let mut data = impulse(p);
+                    Tree::new(false).forward(&mut data, 3);
+                    data[len / 8..len / 4].iter().map(|d| d * d).sum()
+                })
+                .collect(),
+        );
+        assert!(
+            complex * 2.0 < real,
+            "Complex spread {} should be well below real spread {}",
+            complex,
+            real
+        );
+    }
+
+    #[test]
+    fn subbands_are_directional() {
+        // lines at +45° (running up and to the right, with rows running down) appear in the +45°
+        // subband and not the -45° one, and vice versa
+        let (width, height) = (32, 32);
+        let grating = |slope: f64| -> Vec<f64> {
+            (0..width * height)
+                .map(|i| {
+                    let (x, row) = ((i % width) as f64, (i / width) as f64);
+                    (std::f64::consts::PI * (x + slope * row) / 3.0).cos()
+                })
+                .collect()
+        };
+        for (slope, strong, weak) in [
+            (1.0, Direction::Plus45, Direction::Minus45),
+            (-1.0, Direction::Minus45, Direction::Plus45),
+        ] {
+            let wavelet =
+                DualTreeWavelet2D::try_transform(&grating(slope), width, height, 2).unwrap();
+            // the grating's frequency falls in the band of the second level
+            let energy = |direction| -> f64 {
+                let magnitudes = wavelet.magnitudes(2, direction).unwrap();
+                magnitudes.iter().map(|m| m * m).sum()
+            };
+            assert!(
+                energy(strong) > 10.0 * energy(weak),
+                "{:?} energy {} should dominate {:?} energy {}",
+                strong,
+                energy(strong),
+                weak,
+                energy(weak)
+            );
+        }
+
+        let wavelet = DualTreeWavelet2D::transform(&grating(1.0), width, height);
+        assert!(Direction::ALL
+            .iter()
+            .all(|d| wavelet.subband(2, *d).unwrap().len() == 64));
+        assert!(wavelet.subband(6, Direction::Plus15).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+//! Errors reported by the fallible (`try_`) versions of the transforms.
+use std::fmt;
+
+use crate::boundary::BoundaryMode;
+
+/// The reasons a wavelet transform can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WaveletError {
+    /// The data (or coefficients) had `actual` elements, when `expected` were required.
+    InvalidLength { expected: usize, actual: usize },
+    /// More levels of the transform were requested than the data can support.
+    LevelTooDeep { requested: usize, max: usize },
+    /// An orthogonal filter needs a non-empty, even number of coefficients, but had `len`.
+    InvalidFilter { len: usize },
+    /// The filters can't be combined into a filter bank, because they have lengths `scaling` and
+    /// `dual_scaling`.
+    FilterMismatch { scaling: usize, dual_scaling: usize },
+    /// The element at `index` was infinite or NaN.
+    NonFiniteInput { index: usize },
+    /// The wavelet packet nodes don't form a basis: going down any branch of the tree, exactly
+    /// one of them must be found.
+    InvalidBasis,
+    /// The scale at `index` wasn't positive and finite.
+    InvalidScale { index: usize },
+    /// The mother wavelet has no order `order` (it's only a wavelet from order 1).
+    InvalidOrder { order: u32 },
+    /// The transform used boundary mode `mode`, which the operation doesn't support.
+    UnsupportedMode { mode: BoundaryMode },
+    /// The transforms can't be combined, because one has `expected` levels and the other
+    /// `actual`.
+    LevelMismatch { expected: usize, actual: usize },
+    /// The value at `index` wasn't in the domain `0..domain`.
+    OutOfDomain { index: usize, domain: usize },
+    /// The confidence level of an interval wasn't strictly between 0 and 1.
+    InvalidConfidence { confidence: f64 },
+}
+
+impl fmt::Display for WaveletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaveletError::InvalidLength { expected, actual } => {
+                write!(f, "Expected {} elements, but {} were given", expected, actual)
+            }
+            WaveletError::LevelTooDeep { requested, max } => write!(
+                f,
+                "Cannot compute {} levels of the transform, the data supports at most {}",
+                requested, max
+            ),
+            WaveletError::InvalidFilter { len } => write!(
+                f,
+                "An orthogonal filter bank requires an even number of scaling coefficients, but {} were given",
+                len
+            ),
+            WaveletError::FilterMismatch {
+                scaling,
+                dual_scaling,
+            } => write!(
+                f,
+                "Cannot build a filter bank from filters of lengths {} and {}",
+                scaling, dual_scaling
+            ),
+            WaveletError::NonFiniteInput { index } => {
+                write!(f, "The element at position {} is not finite", index)
+            }
+            WaveletError::InvalidBasis => {
+                write!(f, "The wavelet packet nodes do not form a basis")
+            }
+            WaveletError::InvalidScale { index } => {
+                write!(f, "The scale at position {} is not positive and finite", index)
+            }
+            WaveletError::InvalidOrder { order } => {
+                write!(f, "The mother wavelet needs an order of at least 1, but {} was given", order)
+            }
+            WaveletError::UnsupportedMode { mode } => {
+                write!(f, "The boundary mode {:?} is not supported", mode)
+            }
+            WaveletError::LevelMismatch { expected, actual } => write!(
+                f,
+                "Expected a transform with {} levels, but it has {}",
+                expected, actual
+            ),
+            WaveletError::OutOfDomain { index, domain } => write!(
+                f,
+                "The value at position {} is outside the domain 0..{}",
+                index, domain
+            ),
+            WaveletError::InvalidConfidence { confidence } => write!(
+                f,
+                "The confidence level {} is not strictly between 0 and 1",
+                confidence
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WaveletError {}
+
+/// Check that every element of the data is finite.
+pub(crate) fn check_finite<T>(data: &[T]) -> Result<(), WaveletError>
+where
+    T: Into<f64> + Copy,
+{
+    match data.iter().position(|v| !(*v).into().is_finite()) {
+        Some(index) => Err(WaveletError::NonFiniteInput { index }),
+        None => Ok(()),
+    }
+}
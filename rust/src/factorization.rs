@@ -0,0 +1,356 @@
+//! Deriving orthogonal scaling coefficients by spectral factorization.
+//!
+//! The scaling coefficients of an orthogonal wavelet with `2N` coefficients have to satisfy the
+//! admissibility condition `Σ h_n = √2`, the orthonormality conditions `Σ h_n h_{n+2k} = δ_{0,k}`,
+//! and (to get the most out of the coefficients) `N` vanishing moments. Rather than solving those
+//! nonlinear equations directly, it's easier to work with the frequency response
+//! `H(ω) = Σ h_n e^{-iωn}`: the vanishing moments say that `H` has a zero of order `N` at `ω = π`,
+//! and orthonormality says that `|H(ω)|² + |H(ω+π)|² = 2`. Together these give
+//!
+//! ```text
+//! |H(ω)|² = 2 cos^{2N}(ω/2) P(sin²(ω/2)),   P(y) = Σ_{k<N} C(N-1+k, k) y^k
+//! ```
+//!
+//! where `P` is the Daubechies polynomial. So all that's left is to find the "square root" of
+//! `P`: each root `y` of `P` corresponds to a pair of roots `z` and `1/z` of `|H|²` (as a
+//! polynomial in `e^{-iω}`), and taking one of each pair gives a valid `H`. Keeping the roots
+//! outside the unit circle (in `e^{-iω}`) gives the minimum phase solution, which is the
+//! Daubechies wavelet; choosing whichever combination gives the phase closest to linear gives the
+//! least asymmetric solution, which is the Symlet.
+//!
+//! The published Symlet tables don't all follow the same measure of asymmetry, so past the first
+//! few orders the least asymmetric solution derived here isn't always the same as the filter in
+//! [`crate::symlets`] (although it is never more asymmetric, by our measure). Like those tables,
+//! the filter here is always the way round which puts most of its energy first.
+use crate::complex::Complex;
+use crate::error::WaveletError;
+use crate::filter::FilterBank;
+
+/// Which of each pair of roots to keep when factoring the Daubechies polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Keep the roots outside the unit circle, giving the Daubechies wavelets.
+    Minimum,
+    /// Keep the combination of roots whose phase is closest to linear, giving the Symlets.
+    ///
+    /// This tries every combination of roots, so the time taken doubles with every extra pair of
+    /// roots (i.e. every four coefficients).
+    LeastAsymmetric,
+}
+
+/// The number of frequencies at which the phase is measured.
+const PHASE_SAMPLES: usize = 128;
+
+/// Derive the `len` scaling coefficients of an orthogonal wavelet with `len/2` vanishing moments.
+///
+/// The length must be even and non-zero, otherwise this function will panic.
+pub fn scaling_coefficients(len: usize, phase: Phase) -> Vec<f64> {
+    try_scaling_coefficients(len, phase).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Derive scaling coefficients, as in [`scaling_coefficients`], or an error if the length isn't
+/// even and non-zero.
+pub fn try_scaling_coefficients(len: usize, phase: Phase) -> Result<Vec<f64>, WaveletError> {
+    if len == 0 || !len.is_multiple_of(2) {
+        return Err(WaveletError::InvalidFilter { len });
+    }
+    let moments = len / 2;
+    let groups = root_groups(moments);
+
+    let choice = match phase {
+        Phase::Minimum => vec![true; groups.len()],
+        Phase::LeastAsymmetric => {
+            // flipping every choice just reverses the filter, so the first can stay fixed
+            let free = groups.len().saturating_sub(1);
+            (0..1usize << free)
+                .map(|mask| {
+                    let choice = choose(mask, groups.len());
+                    (phase_nonlinearity(&expand(&groups, &choice)), choice)
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, choice)| choice)
+                .unwrap_or_default()
+        }
+    };
+
+    // multiply in the zeros at ω = π
+    let mut poly = expand(&groups, &choice);
+    for _ in 0..moments {
+        poly = multiply(&poly, &[1.0, 1.0]);
+    }
+    let scale = std::f64::consts::SQRT_2 / poly.iter().sum::<f64>();
+    let mut h: Vec<f64> = poly.iter().map(|c| c * scale).collect();
+
+    let centre = (len - 1) as f64 / 2.0;
+    let centroid: f64 = h.iter().enumerate().map(|(n, c)| n as f64 * c * c).sum();
+    if phase == Phase::LeastAsymmetric && centroid > centre {
+        h.reverse();
+    }
+    Ok(h)
+}
+
+/// Derive the filter bank of an orthogonal wavelet with `len` coefficients, as in
+/// [`scaling_coefficients`].
+pub fn filter_bank(len: usize, phase: Phase) -> FilterBank {
+    FilterBank::orthogonal(&scaling_coefficients(len, phase))
+}
+
+/// The roots of `|H|²` outside the unit circle, grouped so that complex conjugates stay together
+/// (otherwise the coefficients wouldn't be real).
+fn root_groups(moments: usize) -> Vec<Vec<Complex>> {
+    let mut binomial = 1.0;
+    let daubechies: Vec<f64> = (0..moments)
+        .map(|k| {
+            let coefficient = binomial;
+            binomial = binomial * (moments + k) as f64 / (k + 1) as f64;
+            coefficient
+        })
+        .collect();
+
+    roots(&daubechies)
+        .into_iter()
+        .filter(|y| y.im >= 0.0)
+        .map(|y| {
+            // y = (2 - z - 1/z)/4, i.e. z² - 2(1-2y)z + 1 = 0
+            let c = Complex::from(1.0) + y * -2.0;
+            let s = (c * c - Complex::from(1.0)).sqrt();
+            let (a, b) = (c + s, c - s);
+            let z = if a.norm() >= b.norm() { a } else { b };
+            if y.im == 0.0 {
+                vec![Complex::from(z.re)]
+            } else {
+                vec![z, z.conj()]
+            }
+        })
+        .collect()
+}
+
+/// Expand the bits of `mask` into a choice for each of `groups` groups, the first always `true`.
+fn choose(mask: usize, groups: usize) -> Vec<bool> {
+    (0..groups)
+        .map(|i| i == 0 || mask & (1 << (i - 1)) == 0)
+        .collect()
+}
+
+/// The real polynomial (in ascending powers) with the chosen roots: the ones in each group for
+/// `true`, or their reciprocals for `false`.
+fn expand(groups: &[Vec<Complex>], choice: &[bool]) -> Vec<f64> {
+    groups
+        .iter()
+        .zip(choice)
+        .fold(vec![1.0], |poly, (group, &outside)| {
+            let root = group[0];
+            let root = if outside {
+                root
+            } else {
+                root.conj() * (1.0 / root.norm_sqr())
+            };
+            if group.len() == 1 {
+                multiply(&poly, &[-root.re, 1.0])
+            } else {
+                multiply(&poly, &[root.norm_sqr(), -2.0 * root.re, 1.0])
+            }
+        })
+}
+
+fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// How far the phase of `Σ c_n e^{-iωn}` over `0 < ω < π` is from a straight line, as the sum of
+/// the squared residuals of the best fit line.
+fn phase_nonlinearity(poly: &[f64]) -> f64 {
+    let mut previous = 0.0;
+    let points: Vec<(f64, f64)> = (0..PHASE_SAMPLES)
+        .map(|k| {
+            let omega = std::f64::consts::PI * (k as f64 + 0.5) / PHASE_SAMPLES as f64;
+            let w = Complex::from_polar(1.0, -omega);
+            let value = poly
+                .iter()
+                .rev()
+                .fold(Complex::default(), |sum, c| sum * w + Complex::from(*c));
+            // unwrap the phase
+            let mut angle = value.arg();
+            let turns = ((previous - angle) / (2.0 * std::f64::consts::PI)).round();
+            angle += turns * 2.0 * std::f64::consts::PI;
+            previous = angle;
+            (omega, angle)
+        })
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let slope = sxy / sxx;
+    points
+        .iter()
+        .map(|p| (p.1 - mean_y - slope * (p.0 - mean_x)).powi(2))
+        .sum()
+}
+
+/// The roots of the real polynomial with coefficients `coefficients` (in ascending powers), by the
+/// Durand-Kerner method. Roots which should be real are returned with a zero imaginary part.
+fn roots(coefficients: &[f64]) -> Vec<Complex> {
+    let degree = coefficients.len() - 1;
+    if degree == 0 {
+        return vec![];
+    }
+    let lead = coefficients[degree];
+    let monic: Vec<f64> = coefficients.iter().map(|c| c / lead).collect();
+    let eval = |z: Complex| {
+        monic
+            .iter()
+            .rev()
+            .fold(Complex::default(), |sum, c| sum * z + Complex::from(*c))
+    };
+
+    // start on a circle which encloses all the roots
+    let radius = 1.0 + monic[..degree].iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    let seed = Complex::from_polar(1.0, 0.4);
+    let mut estimates: Vec<Complex> = (0..degree)
+        .map(|k| {
+            Complex::from_polar(
+                radius,
+                2.0 * std::f64::consts::PI * k as f64 / degree as f64,
+            ) * seed
+        })
+        .collect();
+
+    for _ in 0..1000 {
+        let mut change = 0.0f64;
+        for i in 0..degree {
+            let z = estimates[i];
+            let denominator = estimates
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Complex::from(1.0), |product, (_, r)| product * (z - *r));
+            let step = divide(eval(z), denominator);
+            estimates[i] = z - step;
+            change = change.max(step.norm() / (1.0 + z.norm()));
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+
+    // the roots of a real polynomial are real or come in conjugate pairs, so clean them up to be
+    // exactly that
+    let mut roots: Vec<Complex> = estimates
+        .into_iter()
+        .map(|z| {
+            if z.im.abs() <= 1e-9 * (1.0 + z.norm()) {
+                Complex::from(z.re)
+            } else {
+                z
+            }
+        })
+        .collect();
+    roots.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+    roots
+}
+
+fn divide(a: Complex, b: Complex) -> Complex {
+    a * b.conj() * (1.0 / b.norm_sqr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daubechies::{Daub20, Daub4, Daub8};
+    use crate::filter::Filter;
+    use crate::haar::Haar;
+    use crate::symlets::{Sym10, Sym4, Sym5, Sym6, Sym7};
+
+    fn assert_close(expected: &[f64], actual: &[f64]) {
+        assert_eq!(expected.len(), actual.len());
+        for (pos, (e, a)) in expected.iter().zip(actual).enumerate() {
+            assert!(
+                (e - a).abs() < 1e-10,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                e,
+                a
+            );
+        }
+    }
+
+    #[test]
+    fn derives_daubechies() {
+        assert_close(
+            Haar::filter_bank().scaling(),
+            &scaling_coefficients(2, Phase::Minimum),
+        );
+        assert_close(
+            Daub4::filter_bank().scaling(),
+            &scaling_coefficients(4, Phase::Minimum),
+        );
+        assert_close(
+            Daub8::filter_bank().scaling(),
+            &scaling_coefficients(8, Phase::Minimum),
+        );
+        assert_close(
+            Daub20::filter_bank().scaling(),
+            &scaling_coefficients(20, Phase::Minimum),
+        );
+    }
+
+    #[test]
+    fn derives_symlets() {
+        for bank in [
+            Sym4::filter_bank(),
+            Sym5::filter_bank(),
+            Sym6::filter_bank(),
+            Sym7::filter_bank(),
+        ] {
+            let derived = scaling_coefficients(bank.len(), Phase::LeastAsymmetric);
+            assert_close(bank.scaling(), &derived);
+        }
+
+        // past here the tables use a different measure of asymmetry
+        let table = Sym10::filter_bank();
+        let derived = scaling_coefficients(20, Phase::LeastAsymmetric);
+        assert!(
+            phase_nonlinearity(&derived) <= phase_nonlinearity(table.scaling()) + 1e-9,
+            "The derived Symlet should be no more asymmetric than the table"
+        );
+    }
+
+    #[test]
+    fn derived_filters_are_valid() {
+        for len in [12, 30, 40] {
+            for phase in [Phase::Minimum, Phase::LeastAsymmetric] {
+                let bank = filter_bank(len, phase);
+                assert!(bank.is_admissible(1e-12));
+                assert!(bank.is_orthonormal(1e-9), "{} {:?}", len, phase);
+                // the higher moments are too big to tell apart from zero, so there seem to be more
+                assert!(
+                    bank.vanishing_moments(1e-9) >= len / 2,
+                    "{} {:?}",
+                    len,
+                    phase
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_odd_lengths() {
+        assert_eq!(
+            Err(WaveletError::InvalidFilter { len: 5 }),
+            try_scaling_coefficients(5, Phase::Minimum)
+        );
+        assert_eq!(
+            Err(WaveletError::InvalidFilter { len: 0 }),
+            try_scaling_coefficients(0, Phase::LeastAsymmetric)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! The Fast Fourier Transform, for the transforms which work in the frequency domain.
+use std::f64::consts::PI;
+
+use crate::complex::Complex;
+
+/// The Discrete Fourier Transform `X[k] = Σ x[n] e^(-2πikn/N)`, in place.
+///
+/// The length of the data must be a power of two.
+pub(crate) fn fft(data: &mut [Complex]) {
+    transform(data, -1.0);
+}
+
+/// The inverse Discrete Fourier Transform `x[n] = (1/N) Σ X[k] e^(2πikn/N)`, in place.
+///
+/// The length of the data must be a power of two.
+pub(crate) fn ifft(data: &mut [Complex]) {
+    transform(data, 1.0);
+    let scale = 1.0 / data.len() as f64;
+    for v in data.iter_mut() {
+        *v = *v * scale;
+    }
+}
+
+/// The iterative radix-2 Cooley-Tukey transform, with the exponent's sign given by `sign`.
+fn transform(data: &mut [Complex], sign: f64) {
+    let len = data.len();
+    debug_assert!(len.is_power_of_two(), "FFT length must be a power of two");
+    if len <= 1 {
+        return;
+    }
+
+    // put the data into bit-reversed order
+    let bits = len.trailing_zeros();
+    for i in 0..len {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= len {
+        let step = Complex::from_polar(1.0, sign * 2.0 * PI / size as f64);
+        for block in data.chunks_mut(size) {
+            let (evens, odds) = block.split_at_mut(size / 2);
+            let mut twiddle = Complex::from(1.0);
+            for (e, o) in evens.iter_mut().zip(odds.iter_mut()) {
+                let t = twiddle * *o;
+                *o = *e - t;
+                *e += t;
+                twiddle = twiddle * step;
+            }
+        }
+        size *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_direct_transform() {
+        let data: Vec<Complex> = (0..16)
+            .map(|n| Complex::new((n * n % 7) as f64, (n % 3) as f64 - 1.0))
+            .collect();
+        let mut transform = data.clone();
+        fft(&mut transform);
+
+        for (k, actual) in transform.iter().enumerate() {
+            let expected = data
+                .iter()
+                .enumerate()
+                .fold(Complex::default(), |sum, (n, x)| {
+                    let angle = -2.0 * PI * (k * n) as f64 / 16.0;
+                    sum + *x * Complex::from_polar(1.0, angle)
+                });
+            assert!(
+                (expected - *actual).norm() < 1e-10,
+                "Frequency {} incorrect. Expected {:?} but was {:?}",
+                k,
+                expected,
+                actual
+            );
+        }
+
+        ifft(&mut transform);
+        for (expected, actual) in data.iter().zip(&transform) {
+            assert!((*expected - *actual).norm() < 1e-12);
+        }
+    }
+}
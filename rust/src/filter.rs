@@ -0,0 +1,329 @@
+//! Filter banks, the coefficient sets which define a discrete wavelet.
+//!
+//! A wavelet with compact support is completely described by its finitely many scaling
+//! coefficients `h_n` (see `matrix.md`). Everything else the transform needs--in particular the
+//! wavelet coefficients `g_n`--can be derived from them.
+use crate::error::WaveletError;
+
+/// A two-channel filter bank.
+///
+/// The scaling (low-pass) filter `h_n` produces the averages at each level of the transform,
+/// and the wavelet (high-pass) filter `g_n` produces the difference terms.
+///
+/// Strictly speaking, the transform is computed using the dual filters `~h_n` and `~g_n`, and it is
+/// the primal filters `h_n` and `g_n` which reconstruct the data again. For an orthogonal wavelet
+/// the two sets of filters are the same, but a biorthogonal wavelet uses different filters for
+/// analysis and synthesis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterBank {
+    scaling: Vec<f64>,
+    wavelet: Vec<f64>,
+    dual_scaling: Vec<f64>,
+    dual_wavelet: Vec<f64>,
+}
+
+impl FilterBank {
+    /// Construct the filter bank of an orthogonal wavelet from its scaling coefficients.
+    ///
+    /// The wavelet coefficients are derived using the quadrature mirror relationship
+    /// `g_n = (-1)^n h_{1-n}`. Since we store the coefficients in arrays indexed from zero, we
+    /// shift that relationship by the (even) amount `L-2` so that `g` occupies the same index range
+    /// as `h`, giving `g_n = (-1)^n h_{L-1-n}`. Shifting by an even amount does not affect
+    /// orthogonality, it only moves the wavelet along by a whole number of coefficients.
+    ///
+    /// The scaling coefficients must have an even length, otherwise this function will panic.
+    pub fn orthogonal(scaling: &[f64]) -> FilterBank {
+        Self::try_orthogonal(scaling).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Construct the filter bank of an orthogonal wavelet, as in [`FilterBank::orthogonal`], or
+    /// an error if the scaling coefficients don't have an even length.
+    pub fn try_orthogonal(scaling: &[f64]) -> Result<FilterBank, WaveletError> {
+        if scaling.is_empty() || !scaling.len().is_multiple_of(2) {
+            return Err(WaveletError::InvalidFilter { len: scaling.len() });
+        }
+        let wavelet = quadrature_mirror(scaling);
+
+        Ok(FilterBank {
+            scaling: scaling.to_vec(),
+            wavelet: wavelet.clone(),
+            dual_scaling: scaling.to_vec(),
+            dual_wavelet: wavelet,
+        })
+    }
+
+    /// Construct the filter bank of a biorthogonal wavelet from its primal and dual scaling
+    /// coefficients.
+    ///
+    /// Biorthogonal filters are usually symmetric, and are written down centred on the same point
+    /// but with different lengths. So the shorter filter is padded with zeros on both sides to
+    /// line the centres up again, and if the result has odd length, both are padded with another
+    /// zero at the end. This requires that the two filters have lengths of the same parity,
+    /// otherwise this function will panic.
+    ///
+    /// The wavelet coefficients are then derived from the scaling coefficients of the _other_
+    /// filter, `g_n = (-1)^n ~h_{L-1-n}` and `~g_n = (-1)^n h_{L-1-n}`.
+    pub fn biorthogonal(scaling: &[f64], dual_scaling: &[f64]) -> FilterBank {
+        Self::try_biorthogonal(scaling, dual_scaling).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Construct the filter bank of a biorthogonal wavelet, as in [`FilterBank::biorthogonal`], or
+    /// an error if the filters can't be aligned.
+    pub fn try_biorthogonal(
+        scaling: &[f64],
+        dual_scaling: &[f64],
+    ) -> Result<FilterBank, WaveletError> {
+        if scaling.is_empty()
+            || dual_scaling.is_empty()
+            || scaling.len() % 2 != dual_scaling.len() % 2
+        {
+            return Err(WaveletError::FilterMismatch {
+                scaling: scaling.len(),
+                dual_scaling: dual_scaling.len(),
+            });
+        }
+        let len = scaling.len().max(dual_scaling.len());
+        let len = len + len % 2;
+        let pad = |h: &[f64]| {
+            let front = (len - h.len()) / 2;
+            let mut padded = vec![0.0; front];
+            padded.extend_from_slice(h);
+            padded.resize(len, 0.0);
+            padded
+        };
+        let scaling = pad(scaling);
+        let dual_scaling = pad(dual_scaling);
+
+        Ok(FilterBank {
+            wavelet: quadrature_mirror(&dual_scaling),
+            dual_wavelet: quadrature_mirror(&scaling),
+            scaling,
+            dual_scaling,
+        })
+    }
+
+    /// The scaling coefficients `h_n`.
+    pub fn scaling(&self) -> &[f64] {
+        &self.scaling
+    }
+
+    /// The wavelet coefficients `g_n`.
+    pub fn wavelet(&self) -> &[f64] {
+        &self.wavelet
+    }
+
+    /// The dual scaling coefficients `~h_n`, which are used to compute the averages.
+    pub fn dual_scaling(&self) -> &[f64] {
+        &self.dual_scaling
+    }
+
+    /// The dual wavelet coefficients `~g_n`, which are used to compute the differences.
+    pub fn dual_wavelet(&self) -> &[f64] {
+        &self.dual_wavelet
+    }
+
+    /// True if the dual filters are the same as the primal ones.
+    pub fn is_orthogonal(&self) -> bool {
+        self.scaling == self.dual_scaling
+    }
+
+    /// The number of coefficients in each filter.
+    pub fn len(&self) -> usize {
+        self.scaling.len()
+    }
+
+    /// Always false--a filter bank has at least two coefficients.
+    pub fn is_empty(&self) -> bool {
+        self.scaling.is_empty()
+    }
+
+    /// Check the admissibility condition `sum_n h_n = sqrt(2)`, to within `tolerance`.
+    pub fn is_admissible(&self, tolerance: f64) -> bool {
+        let sum: f64 = self.scaling.iter().sum();
+        (sum - std::f64::consts::SQRT_2).abs() <= tolerance
+    }
+
+    /// Check the normality and orthogonality conditions `sum_n h_n h_{n+2k} = delta_{0,k}`, to
+    /// within `tolerance`.
+    pub fn is_orthonormal(&self, tolerance: f64) -> bool {
+        let h = &self.scaling;
+        (0..h.len().div_ceil(2)).all(|k| {
+            let dot: f64 = (0..h.len() - 2 * k).map(|n| h[n] * h[n + 2 * k]).sum();
+            let expected = if k == 0 { 1.0 } else { 0.0 };
+            (dot - expected).abs() <= tolerance
+        })
+    }
+
+    /// Check the biorthogonality conditions `sum_n h_n ~h_{n+2k} = delta_{0,k}`, to within
+    /// `tolerance`.
+    ///
+    /// These are what guarantee that the synthesis filters invert the analysis filters. For an
+    /// orthogonal wavelet, this is the same as [`FilterBank::is_orthonormal`].
+    pub fn is_biorthogonal(&self, tolerance: f64) -> bool {
+        let h = &self.scaling;
+        let dual = &self.dual_scaling;
+        let len = h.len() as isize;
+        (1 - len / 2..len / 2).all(|k| {
+            let dot: f64 = (0..len)
+                .filter(|n| (0..len).contains(&(n + 2 * k)))
+                .map(|n| h[n as usize] * dual[(n + 2 * k) as usize])
+                .sum();
+            let expected = if k == 0 { 1.0 } else { 0.0 };
+            (dot - expected).abs() <= tolerance
+        })
+    }
+
+    /// The number of vanishing moments of the wavelet.
+    ///
+    /// This is the largest `p` such that `sum_n n^q g_n = 0` for every `q < p`. Since the terms of
+    /// the higher moments get large, each moment is compared against `tolerance` relative to
+    /// the size of its terms.
+    pub fn vanishing_moments(&self, tolerance: f64) -> usize {
+        (0..self.wavelet.len())
+            .take_while(|&q| {
+                let terms = self
+                    .wavelet
+                    .iter()
+                    .enumerate()
+                    .map(|(n, g)| g * (n as f64).powi(q as i32));
+                let (moment, scale) = terms.fold((0.0, 0.0), |(m, s), t| (m + t, s + t.abs()));
+                moment.abs() <= tolerance * scale
+            })
+            .count()
+    }
+
+    /// The norm of the filter which computes the detail coefficients at `level` of the
+    /// transform directly from the signal, i.e. `~g` upsampled by `2^(level-1)`, convolved with
+    /// `~h` upsampled by each smaller power of 2.
+    pub(crate) fn analysis_gain(&self, level: usize) -> f64 {
+        equivalent_gain(self.dual_scaling(), self.dual_wavelet(), level)
+    }
+}
+
+/// The norm of the filter which computes the detail coefficients at `level` of a transform with
+/// the analysis filters `scaling` and `wavelet`, as in [`FilterBank::analysis_gain`].
+pub(crate) fn equivalent_gain(scaling: &[f64], wavelet: &[f64], level: usize) -> f64 {
+    // convolve with each upsampled filter, skipping the zeros between its coefficients
+    let convolve = |signal: &[f64], filter: &[f64], spacing: usize| {
+        let mut product = vec![0.0; signal.len() + (filter.len() - 1) * spacing];
+        for (n, c) in filter.iter().enumerate() {
+            for (k, v) in signal.iter().enumerate() {
+                product[k + n * spacing] += c * v;
+            }
+        }
+        product
+    };
+    let mut equivalent = vec![1.0];
+    for j in 0..level.saturating_sub(1) {
+        equivalent = convolve(&equivalent, scaling, 1 << j);
+    }
+    equivalent = convolve(&equivalent, wavelet, 1 << level.saturating_sub(1));
+    equivalent.iter().map(|c| c * c).sum::<f64>().sqrt()
+}
+
+/// Derive wavelet coefficients from scaling coefficients, `g_n = (-1)^n h_{L-1-n}`.
+fn quadrature_mirror(scaling: &[f64]) -> Vec<f64> {
+    let last = scaling.len() - 1;
+    (0..scaling.len())
+        .map(|n| {
+            if n.is_multiple_of(2) {
+                scaling[last - n]
+            } else {
+                -scaling[last - n]
+            }
+        })
+        .collect()
+}
+
+/// A type-level description of a wavelet.
+///
+/// The transform traits construct their results without any runtime configuration, so a
+/// wavelet type has to carry its coefficients with it. Implementations of this trait are
+/// typically empty marker types which hand out their filter bank on request.
+pub trait Filter {
+    /// The filter bank which defines this wavelet.
+    fn filter_bank() -> FilterBank;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Filter, FilterBank};
+
+    #[test]
+    fn orthogonal_derives_wavelet() {
+        let h = [1.0, 2.0, 3.0, 4.0];
+        let bank = FilterBank::orthogonal(&h);
+
+        assert_eq!(&h, bank.scaling(), "Scaling coefficients changed!");
+        assert_eq!(
+            &[4.0, -3.0, 2.0, -1.0],
+            bank.wavelet(),
+            "Incorrect wavelet coefficients!"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn orthogonal_rejects_odd_length() {
+        FilterBank::orthogonal(&[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn try_constructors_report_errors() {
+        use crate::error::WaveletError;
+
+        assert_eq!(
+            Err(WaveletError::InvalidFilter { len: 3 }),
+            FilterBank::try_orthogonal(&[1.0, 2.0, 3.0])
+        );
+        assert_eq!(
+            Err(WaveletError::FilterMismatch {
+                scaling: 3,
+                dual_scaling: 4
+            }),
+            FilterBank::try_biorthogonal(&[1.0, 2.0, 1.0], &[1.0, 2.0, 2.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn haar_satisfies_conditions() {
+        let bank = crate::haar::Haar::filter_bank();
+
+        assert!(bank.is_admissible(1e-15), "Haar is not admissible!");
+        assert!(bank.is_orthonormal(1e-15), "Haar is not orthonormal!");
+        assert_eq!(1, bank.vanishing_moments(1e-15), "Incorrect moments!");
+    }
+
+    #[test]
+    fn detects_non_orthogonal_coefficients() {
+        // these satisfy admissibility, but neither normality nor orthogonality
+        let h = [0.5, 0.5, 0.5, 0.5];
+        let sum: f64 = h.iter().sum();
+        let bank = FilterBank::orthogonal(&h.map(|v| v * std::f64::consts::SQRT_2 / sum));
+
+        assert!(bank.is_admissible(1e-15), "Should be admissible!");
+        assert!(!bank.is_orthonormal(1e-15), "Should not be orthonormal!");
+    }
+
+    #[test]
+    fn biorthogonal_aligns_centres() {
+        let bank = FilterBank::biorthogonal(&[1.0, 2.0, 1.0], &[-1.0, 2.0, 6.0, 2.0, -1.0]);
+
+        assert_eq!(
+            &[0.0, 1.0, 2.0, 1.0, 0.0, 0.0],
+            bank.scaling(),
+            "Incorrect padding of the scaling coefficients!"
+        );
+        assert_eq!(
+            &[-1.0, 2.0, 6.0, 2.0, -1.0, 0.0],
+            bank.dual_scaling(),
+            "Incorrect padding of the dual scaling coefficients!"
+        );
+        assert_eq!(
+            &[0.0, 1.0, 2.0, -6.0, 2.0, 1.0],
+            bank.wavelet(),
+            "Incorrect wavelet coefficients!"
+        );
+        assert!(!bank.is_orthogonal(), "Should not be orthogonal!");
+    }
+}
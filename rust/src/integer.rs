@@ -0,0 +1,271 @@
+//! Reversible integer-to-integer wavelet transforms.
+//!
+//! The lifting scheme (see [`crate::lifting`]) has a useful property: each step adds _some function_
+//! of one half of the data to the other half, and is undone by subtracting the same function again.
+//! That function doesn't have to be linear--in particular, we can round it to an integer. Then
+//! integer data stays integer all the way through the transform, and the inverse reconstructs it
+//! bit for bit, which is what lossless compression needs.
+//!
+//! The price is that the rounding makes the transform slightly nonlinear, and the scaling step
+//! has to be dropped (it can't be rounded reversibly), so the coefficients are only approximately
+//! those of the real-valued transform, up to a constant factor on each half.
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use crate::arrays;
+use crate::biorthogonal::Bior2_2;
+use crate::haar::Haar;
+
+/// The integer types which the integer transforms operate on.
+///
+/// All the arithmetic wraps on overflow. This doesn't break the reconstruction--each lifting step
+/// is undone by subtracting exactly what it added, so wrapping arithmetic still inverts
+/// bit-exactly. But coefficients which overflowed won't mean much, so data close to the limits of
+/// the type should be transformed in a larger type.
+pub trait Integer: Copy + PartialEq + Debug {
+    /// `self + other`, wrapping on overflow.
+    fn add(self, other: Self) -> Self;
+    /// `self - other`, wrapping on overflow.
+    fn sub(self, other: Self) -> Self;
+    /// `floor(self / 2^bits)`.
+    fn floor_shr(self, bits: u32) -> Self;
+    /// The value 2.
+    fn two() -> Self;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn add(self, other: Self) -> Self {
+                    self.wrapping_add(other)
+                }
+
+                fn sub(self, other: Self) -> Self {
+                    self.wrapping_sub(other)
+                }
+
+                fn floor_shr(self, bits: u32) -> Self {
+                    // arithmetic shifts round towards negative infinity
+                    self >> bits
+                }
+
+                fn two() -> Self {
+                    2
+                }
+            }
+        )*
+    };
+}
+
+integer!(i16, i32, i64);
+
+/// A wavelet which can be computed by integer lifting steps.
+///
+/// The data for a single level is split into its even samples and odd samples (there may be one
+/// more even sample than odd ones), which are replaced by the averages and differences.
+pub trait IntegerLifting {
+    /// Lift the evens and odds into averages and differences, in place.
+    fn forward<I: Integer>(evens: &mut [I], odds: &mut [I]);
+
+    /// Undo [`IntegerLifting::forward`], in place.
+    fn inverse<I: Integer>(evens: &mut [I], odds: &mut [I]);
+}
+
+/// The S-transform, the integer version of Haar.
+///
+/// Each pair is replaced by `d = x_1 - x_0` and `s = x_0 + floor(d/2) = floor((x_0 + x_1)/2)`. If
+/// there's an odd number of samples, the last one has no partner, so it's left as an average.
+impl IntegerLifting for Haar {
+    fn forward<I: Integer>(evens: &mut [I], odds: &mut [I]) {
+        for (s, d) in evens.iter_mut().zip(odds.iter_mut()) {
+            *d = d.sub(*s);
+            *s = s.add(d.floor_shr(1));
+        }
+    }
+
+    fn inverse<I: Integer>(evens: &mut [I], odds: &mut [I]) {
+        for (s, d) in evens.iter_mut().zip(odds.iter_mut()) {
+            *s = s.sub(d.floor_shr(1));
+            *d = d.add(*s);
+        }
+    }
+}
+
+/// The reversible CDF 5/3 transform from JPEG 2000.
+///
+/// `d[k] = x[2k+1] - floor((x[2k] + x[2k+2])/2)` and `s[k] = x[2k] + floor((d[k-1] + d[k] + 2)/4)`,
+/// using whole-point symmetric extension ([`crate::boundary::BoundaryMode::Reflect`]) at the ends.
+/// That extension is what lets the transform keep exactly as many coefficients as samples, even
+/// when there's an odd number of them.
+impl IntegerLifting for Bior2_2 {
+    fn forward<I: Integer>(evens: &mut [I], odds: &mut [I]) {
+        let last = evens.len() - 1;
+        for (k, d) in odds.iter_mut().enumerate() {
+            *d = d.sub(evens[k].add(evens[(k + 1).min(last)]).floor_shr(1));
+        }
+        update(evens, odds, I::add);
+    }
+
+    fn inverse<I: Integer>(evens: &mut [I], odds: &mut [I]) {
+        update(evens, odds, I::sub);
+        let last = evens.len() - 1;
+        for (k, d) in odds.iter_mut().enumerate() {
+            *d = d.add(evens[k].add(evens[(k + 1).min(last)]).floor_shr(1));
+        }
+    }
+}
+
+/// The update step of the 5/3 transform, `s[k] = op(s[k], floor((d[k-1] + d[k] + 2)/4))`.
+fn update<I: Integer>(evens: &mut [I], odds: &[I], op: fn(I, I) -> I) {
+    if odds.is_empty() {
+        return;
+    }
+    let last = odds.len() - 1;
+    for (k, s) in evens.iter_mut().enumerate() {
+        let before = odds[k.saturating_sub(1).min(last)];
+        let after = odds[k.min(last)];
+        *s = op(*s, before.add(after).add(I::two()).floor_shr(2));
+    }
+}
+
+/// A reversible integer wavelet transform.
+///
+/// The transform is held in the layout `[avg | d^J | ... | d^1]`, where level `j` splits its
+/// `n` averages into `ceil(n/2)` averages and `floor(n/2)` differences, all the way down to a single
+/// average. So there are always exactly as many coefficients as samples.
+#[derive(Debug)]
+pub struct IntegerWavelet<L: IntegerLifting, I: Integer> {
+    transform: Vec<I>,
+    lifting: PhantomData<L>,
+}
+
+/// The S-transform (integer Haar).
+pub type IntegerHaarWavelet<I> = IntegerWavelet<Haar, I>;
+
+/// The reversible CDF 5/3 transform.
+pub type IntegerCdf53Wavelet<I> = IntegerWavelet<Bior2_2, I>;
+
+impl<L: IntegerLifting, I: Integer> IntegerWavelet<L, I> {
+    /// The transform coefficients.
+    pub fn coefficients(&self) -> &[I] {
+        &self.transform
+    }
+}
+
+impl<L: IntegerLifting, I: Integer> super::IntegerWaveletTransform<I> for IntegerWavelet<L, I> {
+    fn transform(data: &[I]) -> Self {
+        Self::transform_in_place(data.to_vec())
+    }
+
+    fn transform_in_place(mut data: Vec<I>) -> Self {
+        let mut len = data.len();
+        while len > 1 {
+            arrays::partition_evens(&mut data[..len]);
+            let (evens, odds) = data[..len].split_at_mut(len.div_ceil(2));
+            L::forward(evens, odds);
+            len = len.div_ceil(2);
+        }
+        IntegerWavelet {
+            transform: data,
+            lifting: PhantomData,
+        }
+    }
+
+    fn invert(&self) -> Vec<I> {
+        let mut data = self.transform.clone();
+        inverse::<L, I>(&mut data);
+        data
+    }
+
+    fn invert_in_place(&mut self) -> Vec<I> {
+        inverse::<L, I>(&mut self.transform);
+        std::mem::take(&mut self.transform)
+    }
+}
+
+/// Invert every level of the transform in place.
+fn inverse<L: IntegerLifting, I: Integer>(data: &mut [I]) {
+    let mut lengths = vec![data.len()];
+    while lengths[lengths.len() - 1] > 1 {
+        let last = lengths[lengths.len() - 1];
+        lengths.push(last.div_ceil(2));
+    }
+    for &len in lengths.iter().rev().skip(1) {
+        let (evens, odds) = data[..len].split_at_mut(len.div_ceil(2));
+        L::inverse(evens, odds);
+        arrays::interleave(&mut data[..len]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntegerWaveletTransform;
+
+    const DATA: [i32; 13] = [7, 3, -5, 11, 12, 13, 0, 1, -4, 2, 700, 7, 6];
+
+    #[test]
+    fn s_transform_matches_hand_computation() {
+        let wavelet = IntegerHaarWavelet::transform(&[1, 4, 6, 3]);
+
+        // level 1: pairs (1,4) -> s=2 d=3, (6,3) -> s=4 d=-3
+        // level 2: (2,4) -> s=3 d=2
+        assert_eq!(&[3, 2, 3, -3], wavelet.coefficients());
+    }
+
+    #[test]
+    fn reconstructs_exactly() {
+        for len in 0..=DATA.len() {
+            let data = &DATA[..len];
+            let haar = IntegerHaarWavelet::transform(data);
+            assert_eq!(
+                data,
+                &haar.invert()[..],
+                "S-transform of {} did not invert",
+                len
+            );
+
+            let mut cdf = IntegerCdf53Wavelet::transform(data);
+            assert_eq!(
+                len,
+                cdf.coefficients().len(),
+                "Incorrect number of coefficients"
+            );
+            assert_eq!(
+                data,
+                &cdf.invert_in_place()[..],
+                "5/3 of {} did not invert",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn reconstructs_at_the_limits_of_the_type() {
+        let data: Vec<i64> = vec![i64::MAX, i64::MIN, 0, i64::MAX, -1, i64::MIN, i64::MAX];
+
+        assert_eq!(data, IntegerCdf53Wavelet::transform(&data).invert());
+        assert_eq!(data, IntegerHaarWavelet::transform(&data).invert());
+    }
+
+    #[test]
+    fn cdf53_is_close_to_real_transform() {
+        // without the scaling step, the first level of differences should be those of bior2.2
+        // up to a factor (and rounding)
+        let data: Vec<i32> = (0..16).map(|v| (v * v) % 17).collect();
+        let wavelet = IntegerCdf53Wavelet::transform(&data);
+        let finest = &wavelet.coefficients()[8..];
+        for (k, d) in finest.iter().enumerate() {
+            let right = if k == 7 { data[14] } else { data[2 * k + 2] };
+            let expected = data[2 * k + 1] as f64 - (data[2 * k] + right) as f64 / 2.0;
+            assert!(
+                (*d as f64 - expected).abs() <= 0.5,
+                "Difference {} incorrect. Expected {} but was {}",
+                k,
+                expected,
+                d
+            );
+        }
+    }
+}
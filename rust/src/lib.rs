@@ -1,6 +1,38 @@
 // internal modules
 mod arrays;
-mod haar;
+mod fft;
+#[cfg(test)]
+mod test_util;
+
+pub use error::WaveletError;
+
+// wavelets
+pub mod biorthogonal;
+pub mod boundary;
+pub mod cascade;
+pub mod coiflets;
+pub mod complex;
+pub mod compression;
+pub mod continuous;
+pub mod daubechies;
+pub mod denoising;
+pub mod dual_tree;
+pub mod error;
+pub mod factorization;
+pub mod filter;
+pub mod haar;
+pub mod integer;
+pub mod lifting;
+pub mod linear;
+pub mod modwt;
+pub mod multidimensional;
+pub mod packets;
+pub mod sparse;
+pub mod stationary;
+pub mod symlets;
+pub mod synopsis;
+pub mod two_dimensional;
+pub mod wavelet;
 
 /// A Wavelet Transform.
 ///
@@ -19,6 +51,27 @@ pub trait WaveletTransform {
     /// lifetime.
     fn transform_in_place(data: Vec<f64>) -> Self;
 
+    /// Perform the Discrete Wavelet Transform, as in [`WaveletTransform::transform`], or return an
+    /// error if the data can't be transformed.
+    fn try_transform<T>(data: &[T]) -> Result<Self, WaveletError>
+    where
+        Self: Sized,
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        Ok(Self::transform(data))
+    }
+
+    /// Perform the Wavelet Transform in place, as in [`WaveletTransform::transform_in_place`], or
+    /// return an error if the data can't be transformed.
+    fn try_transform_in_place(data: Vec<f64>) -> Result<Self, WaveletError>
+    where
+        Self: Sized,
+    {
+        error::check_finite(&data)?;
+        Ok(Self::transform_in_place(data))
+    }
+
     /// Invert the Transform.
     ///
     /// This operation is performed on a copy of the data, which does not destroy this instance.
@@ -40,6 +93,26 @@ pub trait WaveletTransform {
     fn invert_in_place(&mut self) -> Vec<f64>;
 }
 
+/// A reversible Wavelet Transform of integer data.
+///
+/// This mirrors [`WaveletTransform`], except that the data stays in the integer type `I`, and the
+/// inverse reconstructs it exactly rather than modulo floating point errors.
+pub trait IntegerWaveletTransform<I: integer::Integer> {
+    /// Perform the transform on the specified data non-destructively.
+    fn transform(data: &[I]) -> Self;
+
+    /// Perform the transform on the passed-in data in place destructively.
+    fn transform_in_place(data: Vec<I>) -> Self;
+
+    /// Invert the Transform.
+    ///
+    /// This operation is performed on a copy of the data, which does not destroy this instance.
+    fn invert(&self) -> Vec<I>;
+
+    /// Invert the Transform in place, moving the underlying memory into the return value.
+    fn invert_in_place(&mut self) -> Vec<I>;
+}
+
 /// A Multi-Resolution Decomposition.
 ///
 /// A Multi-Resolution Decomposition has all the levels of the discrete wavelet transform
@@ -48,6 +121,40 @@ pub trait MRDecomposition<WT: WaveletTransform>: Into<WT> {
     fn decompose<T>(data: &[T]) -> Self
     where
         T: Into<f64> + Copy;
+
+    /// Decompose the data, as in [`MRDecomposition::decompose`], or return an error if the data
+    /// can't be decomposed.
+    fn try_decompose<T>(data: &[T]) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        Ok(Self::decompose(data))
+    }
+}
+
+/// A transform whose detail coefficients can be read and modified level by level, as
+/// [`denoising`] and [`compression`] do.
+pub trait DetailCoefficients {
+    /// The number of levels of detail coefficients.
+    fn detail_levels(&self) -> usize;
+
+    /// The detail coefficients at `level`, where level 1 is the finest, or `None` if there's no
+    /// such level.
+    fn detail_coefficients(&self, level: usize) -> Option<&[f64]>;
+
+    /// The detail coefficients at `level`, for modification.
+    fn detail_coefficients_mut(&mut self, level: usize) -> Option<&mut [f64]>;
+
+    /// The number of samples the transform was computed from.
+    fn signal_len(&self) -> usize;
+
+    /// The standard deviation of the detail coefficients at `level` when the signal is white noise
+    /// with unit variance.
+    ///
+    /// This is 1 at every level for an orthonormal transform, but not for biorthogonal filters,
+    /// or for transforms (like the MODWT) which rescale their filters at each level.
+    fn noise_gain(&self, level: usize) -> f64;
 }
 
 /// Perform the Discrete Wavelet Transform(DWT) on the specified data.
@@ -60,11 +167,11 @@ pub trait MRDecomposition<WT: WaveletTransform>: Into<WT> {
 /// prefer [`dwt_in_place`], as it will reuse an existing memory space to perform its underlying
 /// calculation.
 ///
-/// Note that the discrete wavelet transform requires that the input data be a power of 2. This
-/// function deals with this by padding--if the data isn't sized to be a power of two, then a new
-/// vector will be created which is the next power of 2 higher, and the result will be padded with
-/// zeros. This padding doesn't significantly affect the resulting transform, but ensures that the
-/// calculations can be correctly executed.
+/// The data can be any length. Each level of the transform treats its data as periodic, and if a
+/// level has an odd number of samples, the last one is repeated to make it even. The transform
+/// remembers the original length, so inverting it gives back exactly the data that went in. Other
+/// ways of extending the data are available through [`wavelet::FilterWavelet::transform_with`] and
+/// [`boundary::BoundaryMode`].
 pub fn dwt<T, W>(data: &[T]) -> W
 where
     T: Into<f64> + Copy,
@@ -82,19 +189,30 @@ where
 /// transform/inverse, which results in poorer algorithmic performance than using a copy of the
 /// memory space.
 ///
-/// Note that the discrete wavelet transform requires that the input data be a power of 2. Because
-/// this function operate in place without allocating new memory, this function requires that the
-/// data already be a power of 2--otherwise, the function will panic.
-pub fn dwt_in_place<W>(mut data: Vec<f64>) -> W
+/// The data can be any length, but each level of the transform with an odd number of samples
+/// needs one extra slot, so data which isn't a power of 2 may grow the vector slightly.
+pub fn dwt_in_place<W>(data: Vec<f64>) -> W
 where
     W: WaveletTransform,
 {
-    if data.len() & (data.len() - 1) != 0 {
-        panic!(
-            "The Inverse Discrete Wavelet Transform requires that the data be a power of 2. 
-               Pad out the end of the array with zero elements to ensure that this holds"
-        );
-    }
-
     WaveletTransform::transform_in_place(data)
 }
+
+/// Perform the Discrete Wavelet Transform(DWT) on the specified data, as in [`dwt`], or return an
+/// error if the data can't be transformed (for example, because some of it isn't finite).
+pub fn try_dwt<T, W>(data: &[T]) -> Result<W, WaveletError>
+where
+    T: Into<f64> + Copy,
+    W: WaveletTransform,
+{
+    W::try_transform(data)
+}
+
+/// Perform the Discrete Wavelet Transform(DWT) on the specified data in place, as in
+/// [`dwt_in_place`], or return an error if the data can't be transformed.
+pub fn try_dwt_in_place<W>(data: Vec<f64>) -> Result<W, WaveletError>
+where
+    W: WaveletTransform,
+{
+    W::try_transform_in_place(data)
+}
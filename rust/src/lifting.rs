@@ -0,0 +1,813 @@
+//! The lifting scheme, an alternative way of computing the Discrete Wavelet Transform.
+//!
+//! Instead of convolving the data with the scaling and wavelet filters, lifting splits the data
+//! into its even and odd samples (the "lazy" wavelet transform), and then alternately _predicts_
+//! the odd samples from the even ones, keeping only the prediction error, and _updates_ the even
+//! samples from the odd ones, so that they keep the average of the data. Every biorthogonal filter
+//! bank can be factored into a sequence of such steps, followed by scaling the two halves.
+//!
+//! Each step only adds a multiple of one half of the data into the other half, so it can be
+//! performed in place, and undone by subtracting the same thing again. And because the steps share
+//! their work between the averages and the differences, lifting needs about half the arithmetic
+//! of the convolution.
+use std::marker::PhantomData;
+
+use crate::arrays;
+use crate::biorthogonal::{Bior2_2, Bior4_4};
+use crate::daubechies::Daub4;
+use crate::filter;
+use crate::haar::Haar;
+use crate::DetailCoefficients;
+
+/// A single lifting step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiftingStep {
+    /// Add a filtered copy of the even samples to the odd samples:
+    /// `odd[k] += sum_j c_j even[k+offset+j]`.
+    Predict {
+        coefficients: Vec<f64>,
+        offset: isize,
+    },
+    /// Add a filtered copy of the odd samples to the even samples:
+    /// `even[k] += sum_j c_j odd[k+offset+j]`.
+    Update {
+        coefficients: Vec<f64>,
+        offset: isize,
+    },
+}
+
+/// A factorization of a wavelet filter bank into lifting steps.
+///
+/// A level of the transform applies each step in turn (treating the data as periodic), then
+/// multiplies the even samples by `even_scale` and the odd samples by `odd_scale`. The
+/// coefficients are then the evens rotated left by `even_shift` and the odds by `odd_shift`,
+/// since the factorization only determines the filters up to a shift, and we want to produce the
+/// same coefficients as the convolution does. The rotation only changes where the next level finds
+/// its samples, so (apart from levels with an odd number of samples, which have to be put back in
+/// order to repeat the last one) no data is moved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiftingScheme {
+    steps: Vec<LiftingStep>,
+    even_scale: f64,
+    odd_scale: f64,
+    even_shift: usize,
+    odd_shift: usize,
+}
+
+impl Default for LiftingScheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiftingScheme {
+    /// The lazy wavelet, which has no steps at all.
+    pub fn new() -> LiftingScheme {
+        LiftingScheme {
+            steps: vec![],
+            even_scale: 1.0,
+            odd_scale: 1.0,
+            even_shift: 0,
+            odd_shift: 0,
+        }
+    }
+
+    /// Add a predict step, `odd[k] += sum_j c_j even[k+offset+j]`.
+    pub fn predict(mut self, coefficients: &[f64], offset: isize) -> Self {
+        self.steps.push(LiftingStep::Predict {
+            coefficients: coefficients.to_vec(),
+            offset,
+        });
+        self
+    }
+
+    /// Add an update step, `even[k] += sum_j c_j odd[k+offset+j]`.
+    pub fn update(mut self, coefficients: &[f64], offset: isize) -> Self {
+        self.steps.push(LiftingStep::Update {
+            coefficients: coefficients.to_vec(),
+            offset,
+        });
+        self
+    }
+
+    /// Scale the evens and odds once all the steps are done.
+    pub fn scale(mut self, even: f64, odd: f64) -> Self {
+        self.even_scale = even;
+        self.odd_scale = odd;
+        self
+    }
+
+    /// Rotate the evens and odds left once all the steps are done.
+    pub fn shift(mut self, even: usize, odd: usize) -> Self {
+        self.even_shift = even;
+        self.odd_shift = odd;
+        self
+    }
+
+    /// The lifting steps, in the order they are applied.
+    pub fn steps(&self) -> &[LiftingStep] {
+        &self.steps
+    }
+
+    /// Perform a single level of the transform in place.
+    ///
+    /// The data must have an even length. As lifting normally does, this leaves the coefficients
+    /// interleaved: with `n = data.len() / 2`, average `k` is in `data[2 * ((k + e) % n)]` and
+    /// difference `k` is in `data[2 * ((k + o) % n) + 1]`, where `e` and `o` are the shifts of the
+    /// scheme.
+    pub fn forward(&self, data: &mut [f64]) {
+        let (evens, odds) = View::contiguous(data.len()).halves();
+        self.lift_level(data, evens, odds);
+    }
+
+    /// Undo a single level of the transform in place.
+    ///
+    /// The data must have an even length, and hold the interleaved coefficients produced by
+    /// [`LiftingScheme::forward`].
+    pub fn inverse(&self, data: &mut [f64]) {
+        let (evens, odds) = View::contiguous(data.len()).halves();
+        self.unlift_level(data, evens, odds);
+    }
+
+    /// The analysis filters equivalent to a level of the scheme, `(scaling, wavelet)`, found from
+    /// its response to an impulse at each position.
+    ///
+    /// The filters are only determined up to a shift (and here, come out time-reversed), which
+    /// doesn't change anything which depends only on their norms.
+    fn analysis_filters(&self) -> (Vec<f64>, Vec<f64>) {
+        // long enough that neither filter wraps around onto itself
+        let support: usize = self
+            .steps
+            .iter()
+            .map(|step| match step {
+                LiftingStep::Predict {
+                    coefficients,
+                    offset,
+                }
+                | LiftingStep::Update {
+                    coefficients,
+                    offset,
+                } => coefficients.len() + offset.unsigned_abs(),
+            })
+            .sum();
+        let half = 2 * support + self.even_shift + self.odd_shift + 1;
+        let (average, difference) = (
+            2 * (self.even_shift % half),
+            2 * (self.odd_shift % half) + 1,
+        );
+
+        let (mut scaling, mut wavelet) = (vec![0.0; 2 * half], vec![0.0; 2 * half]);
+        let mut impulse = vec![0.0; 2 * half];
+        for n in 0..2 * half {
+            impulse.fill(0.0);
+            impulse[n] = 1.0;
+            self.forward(&mut impulse);
+            // centre the filters, so that the taps before the coefficient don't wrap around
+            scaling[(n + half - average) % (2 * half)] = impulse[average];
+            wavelet[(n + half - difference + 2 * half) % (2 * half)] = impulse[difference];
+        }
+        (scaling, wavelet)
+    }
+
+    /// Apply the steps and scaling to the samples in `evens` and `odds`.
+    fn lift_level(&self, data: &mut [f64], evens: View, odds: View) {
+        for step in &self.steps {
+            match step {
+                LiftingStep::Predict {
+                    coefficients,
+                    offset,
+                } => lift(data, odds, evens, coefficients, *offset, 1.0),
+                LiftingStep::Update {
+                    coefficients,
+                    offset,
+                } => lift(data, evens, odds, coefficients, *offset, 1.0),
+            }
+        }
+        evens.for_each(data, |v| *v *= self.even_scale);
+        odds.for_each(data, |v| *v *= self.odd_scale);
+    }
+
+    /// Undo [`LiftingScheme::lift_level`].
+    fn unlift_level(&self, data: &mut [f64], evens: View, odds: View) {
+        evens.for_each(data, |v| *v /= self.even_scale);
+        odds.for_each(data, |v| *v /= self.odd_scale);
+        for step in self.steps.iter().rev() {
+            match step {
+                LiftingStep::Predict {
+                    coefficients,
+                    offset,
+                } => lift(data, odds, evens, coefficients, *offset, -1.0),
+                LiftingStep::Update {
+                    coefficients,
+                    offset,
+                } => lift(data, evens, odds, coefficients, *offset, -1.0),
+            }
+        }
+    }
+}
+
+/// A periodic sequence of samples stored at a stride in a larger slice, starting `rotation`
+/// places in: sample `m` is at `base + ((m + rotation) % len) * stride`.
+///
+/// Splitting a view into its even and odd samples gives two more views at twice the stride, so
+/// every level of the transform can work on the data where it is, and the shifts of a scheme
+/// only change the rotation of the views instead of moving any data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct View {
+    base: usize,
+    stride: usize,
+    len: usize,
+    rotation: usize,
+}
+
+impl View {
+    fn contiguous(len: usize) -> View {
+        View {
+            base: 0,
+            stride: 1,
+            len,
+            rotation: 0,
+        }
+    }
+
+    fn index(&self, m: usize) -> usize {
+        self.base + ((m + self.rotation) % self.len) * self.stride
+    }
+
+    /// The even and odd samples of a view with an even length.
+    fn halves(&self) -> (View, View) {
+        let half = |first: usize| View {
+            base: self.base + (first % 2) * self.stride,
+            stride: 2 * self.stride,
+            len: self.len / 2,
+            rotation: (first / 2) % (self.len / 2),
+        };
+        (half(self.rotation), half(self.rotation + 1))
+    }
+
+    fn rotated(&self, by: usize) -> View {
+        View {
+            rotation: (self.rotation + by) % self.len,
+            ..*self
+        }
+    }
+
+    fn for_each(&self, data: &mut [f64], f: impl Fn(&mut f64)) {
+        (0..self.len).for_each(|m| f(&mut data[self.index(m)]));
+    }
+}
+
+/// `dest[k] += sign * sum_j c_j src[k+offset+j]`, wrapping around the end of `src`.
+fn lift(data: &mut [f64], dest: View, src: View, coefficients: &[f64], offset: isize, sign: f64) {
+    let len = src.len as isize;
+    for k in 0..dest.len {
+        let sum: f64 = coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, c)| {
+                c * data[src.index((k as isize + offset + j as isize).rem_euclid(len) as usize)]
+            })
+            .sum();
+        data[dest.index(k)] += sign * sum;
+    }
+}
+
+/// Move the samples of `from` to where `to` would have them (the views differ only in rotation).
+///
+/// That's a rotation of the samples of `to`, which is done in place by three reversals.
+fn realign(data: &mut [f64], from: View, to: View) {
+    let by = (from.rotation + from.len - to.rotation) % from.len;
+    let reverse = |data: &mut [f64], mut lo: usize, mut hi: usize| {
+        while lo + 1 < hi {
+            data.swap(to.index(lo), to.index(hi - 1));
+            lo += 1;
+            hi -= 1;
+        }
+    };
+    reverse(data, 0, by);
+    reverse(data, by, to.len);
+    reverse(data, 0, to.len);
+}
+
+/// Where a single level of the whole transform finds its data.
+#[derive(Debug)]
+struct Level {
+    /// The samples of the level, including the repeated last sample if there is one.
+    samples: View,
+    padded: bool,
+    /// How far the samples had to be rotated back into place before the last one could be
+    /// repeated.
+    realigned: usize,
+    averages: View,
+    details: View,
+}
+
+/// Plan the views for every level of the transform of `len` samples, and return them with the
+/// length of buffer they need.
+///
+/// Levels with an odd number of samples repeat the last one at the end of the view, in the slot
+/// just past it. Nothing else is ever stored there, but it can be past the end of the data.
+fn plan(scheme: &LiftingScheme, len: usize) -> (Vec<Level>, usize) {
+    let mut levels = vec![];
+    let mut size = len;
+    let mut samples = View::contiguous(len);
+    while samples.len > 1 {
+        let mut realigned = 0;
+        let padded = !samples.len.is_multiple_of(2);
+        if padded {
+            // the repeated sample has to follow the last one, so this level can't be rotated
+            realigned = samples.rotation;
+            samples.rotation = 0;
+            samples.len += 1;
+            size = size.max(samples.index(samples.len - 1) + 1);
+        }
+        let (evens, odds) = samples.halves();
+        let averages = evens.rotated(scheme.even_shift);
+        levels.push(Level {
+            samples,
+            padded,
+            realigned,
+            averages,
+            details: odds.rotated(scheme.odd_shift),
+        });
+        samples = averages;
+    }
+    (levels, size)
+}
+
+/// A type-level description of a wavelet by its lifting steps, the lifting equivalent of
+/// [`crate::filter::Filter`].
+pub trait Lifting {
+    /// The lifting scheme which defines this wavelet.
+    fn lifting_scheme() -> LiftingScheme;
+}
+
+impl Lifting for Haar {
+    fn lifting_scheme() -> LiftingScheme {
+        use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+        LiftingScheme::new()
+            .predict(&[-1.0], 0)
+            .update(&[0.5], 0)
+            .scale(SQRT_2, -FRAC_1_SQRT_2)
+    }
+}
+
+impl Lifting for Bior2_2 {
+    fn lifting_scheme() -> LiftingScheme {
+        use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+        LiftingScheme::new()
+            .predict(&[-0.5, -0.5], 0)
+            .update(&[0.25, 0.25], -1)
+            .scale(SQRT_2, -FRAC_1_SQRT_2)
+            .shift(1, 1)
+    }
+}
+
+impl Lifting for Bior4_4 {
+    fn lifting_scheme() -> LiftingScheme {
+        const ALPHA: f64 = -1.5861343420599236;
+        const BETA: f64 = -0.05298011857296141;
+        const GAMMA: f64 = 0.8829110755309333;
+        const DELTA: f64 = 0.44350685204397115;
+        const K: f64 = 1.1496043988602412;
+        LiftingScheme::new()
+            .predict(&[ALPHA, ALPHA], 0)
+            .update(&[BETA, BETA], -1)
+            .predict(&[GAMMA, GAMMA], 0)
+            .update(&[DELTA, DELTA], -1)
+            .scale(K, -1.0 / K)
+            .shift(2, 2)
+    }
+}
+
+impl Lifting for Daub4 {
+    fn lifting_scheme() -> LiftingScheme {
+        let r3 = 3_f64.sqrt();
+        let r2 = std::f64::consts::SQRT_2;
+        LiftingScheme::new()
+            .update(&[r3], 0)
+            .predict(&[(2.0 - r3) / 4.0, -r3 / 4.0], -1)
+            .update(&[-1.0], 1)
+            .scale((r3 - 1.0) / r2, -(r3 + 1.0) / r2)
+            .shift(0, 1)
+    }
+}
+
+/// The Discrete Wavelet Transform computed by lifting.
+///
+/// This produces the same coefficients (up to rounding) as the convolution in
+/// [`crate::wavelet::FilterWavelet`] does with [`crate::boundary::BoundaryMode::Periodization`],
+/// in the same layout `[avg | d^J | ... | d^1]`. Levels with an odd number of samples have the last
+/// one repeated to make them even, in the same way.
+#[derive(Debug)]
+pub struct LiftingWavelet<L: Lifting> {
+    transform: Vec<f64>,
+    len: usize,
+    lifting: PhantomData<L>,
+}
+
+/// The Haar wavelet, computed by lifting.
+pub type HaarLifting = LiftingWavelet<Haar>;
+
+/// The CDF 5/3 (bior2.2) wavelet, computed by lifting.
+pub type Cdf53Lifting = LiftingWavelet<Bior2_2>;
+
+/// The CDF 9/7 (bior4.4) wavelet, computed by lifting.
+pub type Cdf97Lifting = LiftingWavelet<Bior4_4>;
+
+/// The DAUB4 wavelet, computed by lifting.
+pub type Daub4Lifting = LiftingWavelet<Daub4>;
+
+impl<L: Lifting> LiftingWavelet<L> {
+    /// The transform coefficients.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.transform
+    }
+
+    /// The number of samples at each level, starting with the data, as in
+    /// [`crate::wavelet::FilterWavelet`] with [`crate::boundary::BoundaryMode::Periodization`].
+    fn level_lengths(&self) -> Vec<usize> {
+        let mut lengths = vec![self.len];
+        while lengths[lengths.len() - 1] > 1 {
+            let last = lengths[lengths.len() - 1];
+            lengths.push(last.div_ceil(2));
+        }
+        lengths
+    }
+
+    /// Where the differences `d^level` are in the layout `[avg | d^J | ... | d^1]`.
+    fn detail_range(&self, level: usize) -> Option<std::ops::Range<usize>> {
+        let lengths = self.level_lengths();
+        if level == 0 || level >= lengths.len() {
+            return None;
+        }
+        let end = self.transform.len() - lengths[1..level].iter().sum::<usize>();
+        Some(end - lengths[level]..end)
+    }
+}
+
+impl<L: Lifting> DetailCoefficients for LiftingWavelet<L> {
+    fn detail_levels(&self) -> usize {
+        self.level_lengths().len() - 1
+    }
+
+    fn detail_coefficients(&self, level: usize) -> Option<&[f64]> {
+        let range = self.detail_range(level)?;
+        Some(&self.transform[range])
+    }
+
+    fn detail_coefficients_mut(&mut self, level: usize) -> Option<&mut [f64]> {
+        let range = self.detail_range(level)?;
+        Some(&mut self.transform[range])
+    }
+
+    fn signal_len(&self) -> usize {
+        self.len
+    }
+
+    fn noise_gain(&self, level: usize) -> f64 {
+        let (scaling, wavelet) = L::lifting_scheme().analysis_filters();
+        filter::equivalent_gain(&scaling, &wavelet, level)
+    }
+}
+
+impl<L: Lifting> super::WaveletTransform for LiftingWavelet<L> {
+    fn transform<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect())
+    }
+
+    fn transform_in_place(mut data: Vec<f64>) -> Self {
+        let scheme = L::lifting_scheme();
+        let len = data.len();
+        let (levels, size) = plan(&scheme, len);
+        data.reserve_exact(size - len);
+        data.resize(size, 0.0);
+        for level in &levels {
+            let samples = level.samples;
+            if level.padded {
+                let unpadded = View {
+                    len: samples.len - 1,
+                    ..samples
+                };
+                if level.realigned > 0 {
+                    realign(&mut data, unpadded.rotated(level.realigned), unpadded);
+                }
+                data[samples.index(samples.len - 1)] = data[samples.index(samples.len - 2)];
+            }
+            let (evens, odds) = samples.halves();
+            scheme.lift_level(&mut data, evens, odds);
+        }
+
+        // the only reordering: from the views into `[avg | d^J | ... | d^1]`
+        if !levels.is_empty() {
+            arrays::gather(&mut data, slots(&levels, size));
+            data.truncate(coefficients(&levels));
+        }
+        LiftingWavelet {
+            transform: data,
+            len,
+            lifting: PhantomData,
+        }
+    }
+
+    fn invert(&self) -> Vec<f64> {
+        inverse(&L::lifting_scheme(), self.transform.clone(), self.len)
+    }
+
+    fn invert_in_place(&mut self) -> Vec<f64> {
+        inverse(
+            &L::lifting_scheme(),
+            std::mem::take(&mut self.transform),
+            self.len,
+        )
+    }
+}
+
+/// The number of coefficients in the transform planned as `levels`.
+fn coefficients(levels: &[Level]) -> usize {
+    levels.iter().map(|level| level.details.len).sum::<usize>() + 1
+}
+
+/// Map each position of the layout `[avg | d^J | ... | d^1]` to the slot in the views of the
+/// levels where that coefficient is computed.
+///
+/// The repeated samples can leave a few slots of the buffer from [`plan`] unused, which are
+/// mapped to the positions past the end of the coefficients, so this is a permutation of the
+/// whole buffer.
+fn slots(levels: &[Level], size: usize) -> impl Fn(usize) -> usize + '_ {
+    let count = coefficients(levels);
+    // only the repeated samples of the later levels are stored past the samples of the first
+    let repeated: Vec<usize> = levels
+        .iter()
+        .filter(|level| level.padded)
+        .map(|level| level.samples.index(level.samples.len - 1))
+        .collect();
+    let unused: Vec<usize> = (levels[0].samples.len..size)
+        .filter(|slot| !repeated.contains(slot))
+        .collect();
+    // where the details of each level start in the layout, from the deepest level
+    let starts: Vec<usize> = levels
+        .iter()
+        .rev()
+        .scan(1, |pos, level| {
+            let start = *pos;
+            *pos += level.details.len;
+            Some(start)
+        })
+        .collect();
+    move |pos| {
+        if pos >= count {
+            return unused[pos - count];
+        }
+        if pos == 0 {
+            return levels[levels.len() - 1].averages.index(0);
+        }
+        let deepest = starts.partition_point(|&start| start <= pos) - 1;
+        levels[levels.len() - 1 - deepest]
+            .details
+            .index(pos - starts[deepest])
+    }
+}
+
+/// Invert every level of the lifting transform of `len` samples, reusing its buffer.
+fn inverse(scheme: &LiftingScheme, mut data: Vec<f64>, len: usize) -> Vec<f64> {
+    let (levels, size) = plan(scheme, len);
+    if levels.is_empty() {
+        return data;
+    }
+
+    // the only reordering: from `[avg | d^J | ... | d^1]` back into the views
+    data.resize(size, 0.0);
+    arrays::scatter(&mut data, slots(&levels, size));
+    for level in levels.iter().rev() {
+        let (evens, odds) = level.samples.halves();
+        scheme.unlift_level(&mut data, evens, odds);
+        if level.realigned > 0 {
+            let unpadded = View {
+                len: level.samples.len - 1,
+                ..level.samples
+            };
+            realign(&mut data, unpadded, unpadded.rotated(level.realigned));
+        }
+    }
+    data.truncate(len);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wavelet::FilterWavelet;
+    use crate::WaveletTransform;
+
+    const DATA: [f64; 16] = [
+        1.0, 3.0, 5.0, 11.0, 12.0, 13.0, 0.0, 1.0, -4.0, 2.5, 7.0, 7.0, 6.0, 0.5, 3.0, 9.0,
+    ];
+
+    fn assert_matches_convolution<L>()
+    where
+        L: Lifting + crate::filter::Filter,
+    {
+        for len in [1, 2, 5, 8, 13, 16] {
+            let data = &DATA[..len];
+            let lifted = LiftingWavelet::<L>::transform(data);
+            let convolved = FilterWavelet::<L>::transform(data);
+            assert_eq!(
+                convolved.coefficients().len(),
+                lifted.coefficients().len(),
+                "Incorrect number of coefficients"
+            );
+            for (pos, (expected, actual)) in convolved
+                .coefficients()
+                .iter()
+                .zip(lifted.coefficients())
+                .enumerate()
+            {
+                assert!(
+                    (expected - actual).abs() < 1e-12,
+                    "Coefficient {} of {} incorrect. Expected {} but was {}",
+                    pos,
+                    len,
+                    expected,
+                    actual
+                );
+            }
+
+            let inverse = lifted.invert();
+            assert_eq!(len, inverse.len(), "Inverse has the wrong length!");
+            for (pos, expected) in data.iter().enumerate() {
+                assert!(
+                    (expected - inverse[pos]).abs() < 1e-12,
+                    "Element at pos {} incorrect. Expected {} but was {}",
+                    pos,
+                    expected,
+                    inverse[pos]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn haar_matches_convolution() {
+        assert_matches_convolution::<Haar>();
+    }
+
+    #[test]
+    fn cdf53_matches_convolution() {
+        assert_matches_convolution::<Bior2_2>();
+    }
+
+    #[test]
+    fn cdf97_matches_convolution() {
+        assert_matches_convolution::<Bior4_4>();
+    }
+
+    #[test]
+    fn daub4_matches_convolution() {
+        assert_matches_convolution::<Daub4>();
+    }
+
+    #[test]
+    fn leaves_single_level_interleaved() {
+        let mut data = DATA.to_vec();
+        let scheme = Bior2_2::lifting_scheme();
+        scheme.forward(&mut data);
+
+        let convolved = FilterWavelet::<Bior2_2>::try_transform_with(
+            &DATA,
+            crate::boundary::BoundaryMode::Periodization,
+            1,
+        )
+        .unwrap();
+        let n = DATA.len() / 2;
+        for k in 0..n {
+            let average = data[2 * ((k + 1) % n)];
+            let difference = data[2 * ((k + 1) % n) + 1];
+            assert!((convolved.coefficients()[k] - average).abs() < 1e-12);
+            assert!((convolved.coefficients()[n + k] - difference).abs() < 1e-12);
+        }
+
+        scheme.inverse(&mut data);
+        for (pos, expected) in DATA.iter().enumerate() {
+            assert!(
+                (expected - data[pos]).abs() < 1e-12,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                data[pos]
+            );
+        }
+    }
+
+    #[test]
+    fn matches_convolution_for_any_length() {
+        for len in 0..100 {
+            let data: Vec<f64> = (0..len).map(|v| ((v * v * 13) % 23) as f64).collect();
+            let lifted = Cdf97Lifting::transform(&data);
+            let convolved = FilterWavelet::<Bior4_4>::transform(&data);
+            assert_eq!(convolved.coefficients().len(), lifted.coefficients().len());
+            for (pos, (expected, actual)) in convolved
+                .coefficients()
+                .iter()
+                .zip(lifted.coefficients())
+                .enumerate()
+            {
+                assert!(
+                    (expected - actual).abs() < 1e-9,
+                    "Coefficient {} of {} incorrect. Expected {} but was {}",
+                    pos,
+                    len,
+                    expected,
+                    actual
+                );
+            }
+            let inverse = lifted.invert();
+            assert_eq!(len, inverse.len(), "Inverse has the wrong length!");
+            assert!(data.iter().zip(&inverse).all(|(e, a)| (e - a).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn has_the_details_of_the_convolution() {
+        let data: Vec<f64> = (0..37).map(|v| ((v * v * 13) % 23) as f64).collect();
+        let lifted = Cdf97Lifting::transform(&data);
+        let convolved = FilterWavelet::<Bior4_4>::transform(&data);
+        assert_eq!(convolved.detail_levels(), lifted.detail_levels());
+        assert_eq!(37, lifted.signal_len());
+        for level in 1..=lifted.detail_levels() {
+            let (expected, actual) = (
+                convolved.detail_coefficients(level).unwrap(),
+                lifted.detail_coefficients(level).unwrap(),
+            );
+            assert_eq!(
+                expected.len(),
+                actual.len(),
+                "Level {} has the wrong length",
+                level
+            );
+            assert!(expected
+                .iter()
+                .zip(actual)
+                .all(|(e, a)| (e - a).abs() < 1e-9));
+            assert!(
+                (convolved.noise_gain(level) - lifted.noise_gain(level)).abs() < 1e-12,
+                "Level {} noise gain incorrect. Expected {} but was {}",
+                level,
+                convolved.noise_gain(level),
+                lifted.noise_gain(level)
+            );
+        }
+        assert!(lifted.detail_coefficients(0).is_none());
+        assert!(lifted.detail_coefficients(7).is_none());
+
+        // so it can be denoised, with the same result
+        use crate::denoising::{self, Noise, Shrinkage, Threshold};
+        let (threshold, noise) = (Threshold::Universal, Noise::Known(2.0));
+        let expected = denoising::denoise::<FilterWavelet<Bior4_4>, _>(
+            &data,
+            threshold,
+            noise,
+            Shrinkage::Soft,
+        );
+        let actual =
+            denoising::denoise::<Cdf97Lifting, _>(&data, threshold, noise, Shrinkage::Soft);
+        let (expected_thresholds, actual_thresholds) = (
+            &expected.thresholds.thresholds,
+            &actual.thresholds.thresholds,
+        );
+        assert!(expected_thresholds
+            .iter()
+            .zip(actual_thresholds)
+            .all(|(e, a)| (e - a).abs() < 1e-9));
+        assert!(expected
+            .signal
+            .iter()
+            .zip(&actual.signal)
+            .all(|(e, a)| (e - a).abs() < 1e-9));
+    }
+
+    #[test]
+    fn inverts_in_place() {
+        // with room for the repeated samples, the transform and its inverse never reallocate
+        let mut data = Vec::with_capacity(2 * DATA.len());
+        data.extend_from_slice(&DATA[..13]);
+        let buffer = data.as_ptr();
+        let mut wavelet = Cdf97Lifting::transform_in_place(data);
+        assert_eq!(buffer, wavelet.coefficients().as_ptr());
+        let inverse = wavelet.invert_in_place();
+        assert_eq!(buffer, inverse.as_ptr());
+        assert_eq!(13, inverse.len());
+        for (pos, expected) in DATA[..13].iter().enumerate() {
+            assert!(
+                (expected - inverse[pos]).abs() < 1e-12,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                inverse[pos]
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+// Wavelets built on the linear spline (hat function) scaling function `1-|x|`.
+use std::collections::HashMap;
+use std::f64::consts::{PI, SQRT_2};
+use std::sync::{Mutex, OnceLock};
+
+use crate::biorthogonal::Bior2_2;
+use crate::filter::{Filter, FilterBank};
+use crate::wavelet::{
+    BiorthogonalDecomposition, BiorthogonalWavelet, OrthogonalDecomposition, OrthogonalWavelet,
+};
+
+/// The biorthogonal linear spline wavelet.
+///
+/// The hat function satisfies the refinement equation exactly with the three coefficients
+/// `h = (1/2, 1, 1/2) / sqrt(2)`, but its integer translates aren't orthogonal, so (as `matrix.md`
+/// discovers) there is no way to satisfy the orthogonality condition with them. Instead, we can
+/// reconstruct with the hat function and analyse with a dual scaling function. This is the
+/// bior2.2 (CDF 5/3) wavelet, which has the shortest such dual.
+pub type LinearSpline = Bior2_2;
+
+/// The Discrete Wavelet Transform using the biorthogonal linear spline wavelet.
+pub type LinearSplineWavelet = BiorthogonalWavelet<LinearSpline>;
+
+/// The Multi-Resolution Decomposition using the biorthogonal linear spline wavelet.
+pub type LinearSplineDecomposition = BiorthogonalDecomposition<LinearSpline>;
+
+/// The linear Battle-Lemarie wavelet, truncated to `2*HALF_WIDTH+1` coefficients.
+///
+/// This is the other way out of the problem with the hat function: orthogonalize it. The
+/// orthonormal scaling function is the piecewise linear function whose Fourier transform is
+/// `phi^(w) / sqrt(S(w))`, where `phi^` is the transform of the hat function and
+/// `S(w) = sum_k |phi^(w + 2 pi k)|^2 = (2 + cos(w))/3`. Its refinement filter is then
+///
+/// `m0(w) = cos^2(w/2) sqrt(S(w) / S(2w))`
+///
+/// and the scaling coefficients are the Fourier coefficients of `sqrt(2) m0`. Unfortunately, there
+/// are infinitely many of them, so this is not a wavelet with compact support. However they decay
+/// exponentially (roughly like `2^-n`), so truncating them to the `2*HALF_WIDTH+1` coefficients
+/// around the centre gives a filter which is orthogonal to within about `2^-HALF_WIDTH`. Since
+/// the transform inverts using the same filter, the same error shows up in the reconstruction.
+/// [`LinearBattleLemarie`] truncates at the point where this error is comparable to floating point
+/// round-off.
+///
+/// The odd number of coefficients is padded with a zero at the end, so that the quadrature mirror
+/// relationship lines up the wavelet coefficients on the same index range.
+#[derive(Debug)]
+pub struct BattleLemarie<const HALF_WIDTH: usize>;
+
+impl<const HALF_WIDTH: usize> Filter for BattleLemarie<HALF_WIDTH> {
+    fn filter_bank() -> FilterBank {
+        // Computing the coefficients is expensive, and the transforms ask for the filter bank
+        // several times per call, so each truncation is only computed once. Rust doesn't allow a
+        // static per const parameter, so they share a cache keyed by the truncation.
+        static BANKS: OnceLock<Mutex<HashMap<usize, FilterBank>>> = OnceLock::new();
+        let mut banks = BANKS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        banks
+            .entry(HALF_WIDTH)
+            .or_insert_with(|| battle_lemarie(HALF_WIDTH))
+            .clone()
+    }
+}
+
+/// Compute the scaling coefficients of [`BattleLemarie`] from the Fourier series of its refinement
+/// filter.
+fn battle_lemarie(half_width: usize) -> FilterBank {
+    // The coefficients are a Fourier series of a smooth periodic function, so the trapezoid
+    // rule converges exponentially quickly. We want plenty of samples relative to the
+    // frequencies we're extracting.
+    let samples = 64 * (half_width + 1);
+    let response: Vec<f64> = (0..samples)
+        .map(|j| {
+            let w = 2.0 * PI * j as f64 / samples as f64;
+            let c = (w / 2.0).cos();
+            c * c * ((2.0 + w.cos()) / (2.0 + (2.0 * w).cos())).sqrt()
+        })
+        .collect();
+
+    let mut scaling: Vec<f64> = (0..=2 * half_width)
+        .map(|pos| {
+            let n = pos as f64 - half_width as f64;
+            let sum: f64 = response
+                .iter()
+                .enumerate()
+                .map(|(j, m)| m * (2.0 * PI * n * j as f64 / samples as f64).cos())
+                .sum();
+            SQRT_2 * sum / samples as f64
+        })
+        .collect();
+
+    // put back the mass that was truncated off, so that the filter remains admissible
+    let sum: f64 = scaling.iter().sum();
+    scaling.iter_mut().for_each(|h| *h *= SQRT_2 / sum);
+    scaling.push(0.0);
+    FilterBank::orthogonal(&scaling)
+}
+
+/// The linear Battle-Lemarie wavelet, truncated far enough to be orthogonal to within floating
+/// point precision.
+pub type LinearBattleLemarie = BattleLemarie<48>;
+
+/// The Discrete Wavelet Transform using the linear Battle-Lemarie wavelet.
+pub type LinearBattleLemarieWavelet = OrthogonalWavelet<LinearBattleLemarie>;
+
+/// The Multi-Resolution Decomposition using the linear Battle-Lemarie wavelet.
+pub type LinearBattleLemarieDecomposition = OrthogonalDecomposition<LinearBattleLemarie>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::BoundaryMode;
+    use crate::WaveletTransform;
+
+    #[test]
+    fn battle_lemarie_matches_known_coefficients() {
+        // the central coefficients h_0, h_1, h_2, h_3 of the orthonormal linear spline
+        let expected = [0.8176464, 0.3972975, -0.0691010, -0.0519453];
+        let bank = LinearBattleLemarie::filter_bank();
+        for (n, expected) in expected.iter().enumerate() {
+            let actual = bank.scaling()[48 + n];
+            assert!(
+                (expected - actual).abs() < 1e-6,
+                "Coefficient h_{} incorrect. Expected {} but was {}",
+                n,
+                expected,
+                actual
+            );
+            assert_eq!(
+                actual,
+                bank.scaling()[48 - n],
+                "Coefficients are not symmetric!"
+            );
+        }
+    }
+
+    #[test]
+    fn truncation_controls_orthogonality() {
+        assert!(
+            !BattleLemarie::<4>::filter_bank().is_orthonormal(1e-4),
+            "A short truncation should not be orthonormal"
+        );
+        assert!(
+            BattleLemarie::<12>::filter_bank().is_orthonormal(1e-4),
+            "Truncation at 12 should be orthonormal to within 1e-4"
+        );
+        let bank = LinearBattleLemarie::filter_bank();
+        assert!(bank.is_orthonormal(1e-13), "Should be orthonormal");
+        assert!(bank.is_admissible(1e-15), "Should be admissible");
+    }
+
+    #[test]
+    fn transforms_invert() {
+        let data: Vec<f64> = (0..64).map(|v| ((v * 37) % 23) as f64 - 11.0).collect();
+
+        let inverse = LinearBattleLemarieWavelet::transform(&data).invert();
+        let delta = 1e-10;
+        for (pos, expected) in data.iter().enumerate() {
+            assert!(
+                (expected - inverse[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                inverse[pos]
+            );
+        }
+    }
+
+    /// Reconstruct the data from the transform with its finest difference terms removed, and
+    /// return the largest error.
+    fn approximation_error(bank: &FilterBank, data: &[f64]) -> f64 {
+        let mut transform = crate::wavelet::dwt(bank, data, BoundaryMode::Periodization, 1);
+        let half = transform.len() / 2;
+        transform[half..].iter_mut().for_each(|d| *d = 0.0);
+        crate::wavelet::inverse_dwt(bank, &transform, data.len(), BoundaryMode::Periodization, 1)
+            .iter()
+            .zip(data)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn linear_approximations_are_better_for_smooth_data() {
+        let data: Vec<f64> = (0..64)
+            .map(|v| (2.0 * PI * v as f64 / 64.0).sin())
+            .collect();
+
+        let haar = approximation_error(&crate::haar::Haar::filter_bank(), &data);
+        let spline = approximation_error(&LinearSpline::filter_bank(), &data);
+        let battle_lemarie = approximation_error(&LinearBattleLemarie::filter_bank(), &data);
+        assert!(
+            spline < haar / 4.0,
+            "Linear spline error {} is not much better than Haar error {}",
+            spline,
+            haar
+        );
+        assert!(
+            battle_lemarie < haar / 4.0,
+            "Battle-Lemarie error {} is not much better than Haar error {}",
+            battle_lemarie,
+            haar
+        );
+    }
+}
@@ -0,0 +1,483 @@
+//! The Maximal Overlap Discrete Wavelet Transform (MODWT), and the wavelet variance.
+//!
+//! The MODWT is the stationary transform (see [`crate::stationary`]) as it's used in time series
+//! analysis (following Percival and Walden, _Wavelet Methods for Time Series Analysis_). The
+//! filters are rescaled by `1/√2`, so that the coefficients at each level measure changes in
+//! averages over scales of `2^(j-1)` samples directly, and the signal is extended periodically,
+//! so it can have any length. For an orthogonal wavelet, the energy of the signal is then split
+//! exactly between the levels:
+//!
+//! ```text
+//! ‖X‖² = ‖W_1‖² + ... + ‖W_J‖² + ‖V_J‖²
+//! ```
+//!
+//! which makes `‖W_j‖²/N` an estimate of the variance of the signal at scale `2^(j-1)`: the
+//! _wavelet variance_. The coefficients at the start of each level wrap around the end of the
+//! signal, though, so the unbiased estimator ([`MaximalOverlapWavelet::wavelet_variance`]) uses
+//! only the rest.
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::marker::PhantomData;
+
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::haar::Haar;
+use crate::DetailCoefficients;
+
+/// The Maximal Overlap Discrete Wavelet Transform of a wavelet with compact support.
+///
+/// The transform holds the wavelet coefficients `W_j` at each level, and the scaling coefficients
+/// `V_J` at the coarsest level, each as long as the signal.
+#[derive(Debug)]
+pub struct MaximalOverlapWavelet<F: Filter> {
+    scaling: Vec<f64>,
+    details: Vec<Vec<f64>>,
+    filter: PhantomData<F>,
+}
+
+/// The Haar MODWT.
+pub type HaarModwt = MaximalOverlapWavelet<Haar>;
+
+/// An estimate of the wavelet variance at a single level, with a confidence interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveletVariance {
+    /// The level, where level 1 is the finest.
+    pub level: usize,
+    /// The unbiased estimate of the wavelet variance.
+    pub variance: f64,
+    /// The lower end of the confidence interval.
+    pub lower: f64,
+    /// The upper end of the confidence interval.
+    pub upper: f64,
+    /// The equivalent degrees of freedom of the chi-square distribution of the estimate.
+    pub degrees_of_freedom: f64,
+}
+
+impl<F: Filter> MaximalOverlapWavelet<F> {
+    /// Perform `levels` levels of the transform, or return an error if the data isn't finite, or
+    /// can't support that many levels.
+    pub fn try_transform_with<T>(data: &[T], levels: usize) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let max = Self::max_levels(data.len());
+        if levels > max {
+            return Err(WaveletError::LevelTooDeep {
+                requested: levels,
+                max,
+            });
+        }
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Ok(Self::transform_levels(floats, levels))
+    }
+
+    /// The most levels of the transform that `len` samples can support.
+    pub fn max_levels(len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        len.ilog2() as usize
+    }
+
+    fn transform_levels(mut scaling: Vec<f64>, levels: usize) -> Self {
+        let bank = F::filter_bank();
+        let mut details = Vec::with_capacity(levels);
+        let mut next = vec![0.0; scaling.len()];
+        for level in 0..levels {
+            let mut detail = vec![0.0; scaling.len()];
+            level_modwt(&bank, &scaling, 1 << level, &mut next, &mut detail);
+            std::mem::swap(&mut scaling, &mut next);
+            details.push(detail);
+        }
+        MaximalOverlapWavelet {
+            scaling,
+            details,
+            filter: PhantomData,
+        }
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.details.len()
+    }
+
+    /// The wavelet coefficients `W_j` at `level`, where level 1 is the finest, or `None` if
+    /// there's no such level.
+    pub fn wavelet_coefficients(&self, level: usize) -> Option<&[f64]> {
+        self.details.get(level.checked_sub(1)?).map(|d| &d[..])
+    }
+
+    /// The scaling coefficients `V_J` at the coarsest level.
+    pub fn scaling_coefficients(&self) -> &[f64] {
+        &self.scaling
+    }
+
+    /// The detail series `D_j` of the multiresolution analysis at `level`, or `None` if there's
+    /// no such level.
+    ///
+    /// This is the part of the signal which is reconstructed from the wavelet coefficients at
+    /// that level alone. The details at every level and the smooth series sum back to the signal.
+    pub fn detail(&self, level: usize) -> Option<Vec<f64>> {
+        self.wavelet_coefficients(level)?;
+        let zeros = vec![0.0; self.scaling.len()];
+        Some(self.synthesize(zeros, |j| j + 1 == level))
+    }
+
+    /// The smooth series `S_J` of the multiresolution analysis, which is reconstructed from the
+    /// scaling coefficients alone.
+    pub fn smooth(&self) -> Vec<f64> {
+        self.synthesize(self.scaling.clone(), |_| false)
+    }
+
+    /// The unbiased estimate of the wavelet variance at `level`, with a confidence interval at
+    /// the given `confidence` (such as 0.95).
+    ///
+    /// Only the coefficients which don't depend on the periodic extension are used, so this
+    /// returns `None` if there are none of those (or no such level). The interval assumes that
+    /// the estimate is chi-square distributed, with the equivalent degrees of freedom of
+    /// Percival and Walden's third approximation, `max(M_j/2^j, 1)` for `M_j` coefficients.
+    ///
+    /// This panics if the confidence isn't strictly between 0 and 1.
+    pub fn wavelet_variance(&self, level: usize, confidence: f64) -> Option<WaveletVariance> {
+        self.try_wavelet_variance(level, confidence)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The wavelet variance at `level`, as in [`MaximalOverlapWavelet::wavelet_variance`], or an
+    /// error if the confidence isn't strictly between 0 and 1.
+    pub fn try_wavelet_variance(
+        &self,
+        level: usize,
+        confidence: f64,
+    ) -> Result<Option<WaveletVariance>, WaveletError> {
+        check_confidence(confidence)?;
+        Ok(self.variance(level, confidence))
+    }
+
+    fn variance(&self, level: usize, confidence: f64) -> Option<WaveletVariance> {
+        let coefficients = self.wavelet_coefficients(level)?;
+        let len = coefficients.len();
+        let width = ((1usize << level) - 1) * (F::filter_bank().len() - 1) + 1;
+        if width > len {
+            return None;
+        }
+        let count = len - width + 1;
+        let variance = coefficients[width - 1..].iter().map(|w| w * w).sum::<f64>() / count as f64;
+
+        let degrees_of_freedom = (count as f64 / (1u64 << level) as f64).max(1.0);
+        let tail = (1.0 - confidence) / 2.0;
+        Some(WaveletVariance {
+            level,
+            variance,
+            lower: degrees_of_freedom * variance
+                / chi_square_quantile(1.0 - tail, degrees_of_freedom),
+            upper: degrees_of_freedom * variance / chi_square_quantile(tail, degrees_of_freedom),
+            degrees_of_freedom,
+        })
+    }
+
+    /// The wavelet variance at every level which has an estimate, as in
+    /// [`MaximalOverlapWavelet::wavelet_variance`].
+    ///
+    /// This panics if the confidence isn't strictly between 0 and 1.
+    pub fn wavelet_variances(&self, confidence: f64) -> Vec<WaveletVariance> {
+        self.try_wavelet_variances(confidence)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The wavelet variance at every level which has an estimate, or an error if the confidence
+    /// isn't strictly between 0 and 1.
+    pub fn try_wavelet_variances(
+        &self,
+        confidence: f64,
+    ) -> Result<Vec<WaveletVariance>, WaveletError> {
+        check_confidence(confidence)?;
+        Ok((1..=self.levels())
+            .filter_map(|level| self.variance(level, confidence))
+            .collect())
+    }
+
+    /// Reconstruct the signal from the scaling coefficients `scaling`, and the wavelet
+    /// coefficients at every level `j` (counting from 0) for which `use_detail(j)` holds.
+    fn synthesize<U>(&self, mut scaling: Vec<f64>, use_detail: U) -> Vec<f64>
+    where
+        U: Fn(usize) -> bool,
+    {
+        let bank = F::filter_bank();
+        let mut previous = vec![0.0; scaling.len()];
+        for j in (0..self.levels()).rev() {
+            let detail = Some(&self.details[j][..]).filter(|_| use_detail(j));
+            level_inverse_modwt(&bank, &scaling, detail, 1 << j, &mut previous);
+            std::mem::swap(&mut scaling, &mut previous);
+        }
+        scaling
+    }
+}
+
+impl<F: Filter> super::WaveletTransform for MaximalOverlapWavelet<F> {
+    fn transform<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect())
+    }
+
+    fn transform_in_place(data: Vec<f64>) -> Self {
+        let levels = Self::max_levels(data.len());
+        Self::transform_levels(data, levels)
+    }
+
+    fn invert(&self) -> Vec<f64> {
+        self.synthesize(self.scaling.clone(), |_| true)
+    }
+
+    fn invert_in_place(&mut self) -> Vec<f64> {
+        let scaling = std::mem::take(&mut self.scaling);
+        let data = self.synthesize(scaling, |_| true);
+        self.details.clear();
+        data
+    }
+}
+
+impl<F: Filter> DetailCoefficients for MaximalOverlapWavelet<F> {
+    fn detail_levels(&self) -> usize {
+        self.levels()
+    }
+
+    fn detail_coefficients(&self, level: usize) -> Option<&[f64]> {
+        self.wavelet_coefficients(level)
+    }
+
+    fn detail_coefficients_mut(&mut self, level: usize) -> Option<&mut [f64]> {
+        self.details
+            .get_mut(level.checked_sub(1)?)
+            .map(|d| &mut d[..])
+    }
+
+    fn signal_len(&self) -> usize {
+        self.scaling.len()
+    }
+
+    fn noise_gain(&self, level: usize) -> f64 {
+        // each level's filters are rescaled by another 1/√2
+        F::filter_bank().analysis_gain(level) * FRAC_1_SQRT_2.powi(level as i32)
+    }
+}
+
+/// A single level of the MODWT, with the filters upsampled by `step`.
+///
+/// `scaling[t] = Σ ~h_l src[t - step*l] / √2` and `detail[t] = Σ ~g_l src[t - step*l] / √2`,
+/// periodically.
+fn level_modwt(
+    bank: &FilterBank,
+    src: &[f64],
+    step: usize,
+    scaling: &mut [f64],
+    detail: &mut [f64],
+) {
+    let len = src.len();
+    let dual_scaling = bank.dual_scaling();
+    let dual_wavelet = bank.dual_wavelet();
+    for t in 0..len {
+        let (mut v, mut w) = (0.0, 0.0);
+        for l in 0..bank.len() {
+            let x = src[(t + len - (step * l) % len) % len];
+            v += dual_scaling[l] * x;
+            w += dual_wavelet[l] * x;
+        }
+        scaling[t] = v * FRAC_1_SQRT_2;
+        detail[t] = w * FRAC_1_SQRT_2;
+    }
+}
+
+/// Invert a single level of the MODWT, with the filters upsampled by `step`, leaving out the
+/// wavelet coefficients if there are none.
+///
+/// `dest[t] = Σ (h_l scaling[t + step*l] + g_l detail[t + step*l]) / √2`, periodically.
+fn level_inverse_modwt(
+    bank: &FilterBank,
+    scaling: &[f64],
+    detail: Option<&[f64]>,
+    step: usize,
+    dest: &mut [f64],
+) {
+    let len = scaling.len();
+    let filter_scaling = bank.scaling();
+    let filter_wavelet = bank.wavelet();
+    for (t, value) in dest.iter_mut().enumerate() {
+        let mut sum = 0.0;
+        for l in 0..bank.len() {
+            let pos = (t + step * l) % len;
+            sum += filter_scaling[l] * scaling[pos];
+            if let Some(detail) = detail {
+                sum += filter_wavelet[l] * detail[pos];
+            }
+        }
+        *value = sum * FRAC_1_SQRT_2;
+    }
+}
+
+/// Check that a confidence level leaves some probability both inside and outside its interval.
+fn check_confidence(confidence: f64) -> Result<(), WaveletError> {
+    if confidence > 0.0 && confidence < 1.0 {
+        Ok(())
+    } else {
+        Err(WaveletError::InvalidConfidence { confidence })
+    }
+}
+
+/// The quantile of the chi-square distribution with `degrees_of_freedom` degrees of freedom at
+/// probability `p`, by the Wilson-Hilferty approximation.
+fn chi_square_quantile(p: f64, degrees_of_freedom: f64) -> f64 {
+    let scale = 2.0 / (9.0 * degrees_of_freedom);
+    let cube = 1.0 - scale + normal_quantile(p) * scale.sqrt();
+    degrees_of_freedom * cube.max(0.0).powi(3)
+}
+
+/// The quantile of the standard normal distribution at probability `p`, by Acklam's rational
+/// approximation (accurate to about `1e-9`).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996e0,
+        3.754408661907416e0,
+    ];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biorthogonal::Bior2_4;
+    use crate::daubechies::Daub4;
+    use crate::test_util::{assert_close, DATA};
+    use crate::WaveletTransform;
+
+    /// Uniform noise with unit variance, from a linear congruential generator.
+    fn noise(len: usize) -> Vec<f64> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 12f64.sqrt()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn inverts_any_length() {
+        for len in [1, 2, 7, 8, 13] {
+            let data = &DATA[..len];
+            let daub4 = MaximalOverlapWavelet::<Daub4>::transform(data);
+            assert_close(data, &daub4.invert(), "Daub4");
+
+            let mut bior = MaximalOverlapWavelet::<Bior2_4>::transform(data);
+            assert_close(data, &bior.invert_in_place(), "Bior2_4");
+        }
+    }
+
+    #[test]
+    fn preserves_energy() {
+        let wavelet = MaximalOverlapWavelet::<Daub4>::try_transform_with(&DATA, 3).unwrap();
+        let energy = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>();
+
+        let expected = energy(&DATA);
+        let actual = (1..=3)
+            .map(|level| energy(wavelet.wavelet_coefficients(level).unwrap()))
+            .sum::<f64>()
+            + energy(wavelet.scaling_coefficients());
+        assert!(
+            (expected - actual).abs() < 1e-10,
+            "Energy not preserved. Expected {} but was {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn multiresolution_analysis_sums_to_signal() {
+        let wavelet = MaximalOverlapWavelet::<Daub4>::try_transform_with(&DATA, 3).unwrap();
+        let mut sum = wavelet.smooth();
+        for level in 1..=3 {
+            for (s, d) in sum.iter_mut().zip(wavelet.detail(level).unwrap()) {
+                *s += d;
+            }
+        }
+        assert_close(&DATA, &sum, "Multiresolution analysis");
+        assert!(wavelet.detail(4).is_none());
+    }
+
+    #[test]
+    fn estimates_variance_of_white_noise() {
+        // white noise with unit variance has wavelet variance 2^-j at level j
+        let wavelet = HaarModwt::try_transform_with(&noise(4096), 4).unwrap();
+        let variances = wavelet.wavelet_variances(0.95);
+        assert_eq!(4, variances.len());
+        for estimate in variances {
+            let expected = 1.0 / (1 << estimate.level) as f64;
+            assert!(
+                estimate.lower < expected && expected < estimate.upper,
+                "Level {} interval ({}, {}) should contain {}",
+                estimate.level,
+                estimate.lower,
+                estimate.upper,
+                expected
+            );
+            assert!(estimate.lower < estimate.variance && estimate.variance < estimate.upper);
+        }
+
+        for confidence in [0.0, 1.0, -0.5, 1.5, f64::NAN] {
+            assert!(matches!(
+                wavelet.try_wavelet_variance(1, confidence),
+                Err(WaveletError::InvalidConfidence { .. })
+            ));
+            assert!(wavelet.try_wavelet_variances(confidence).is_err());
+        }
+    }
+
+    #[test]
+    fn normal_quantiles_are_accurate() {
+        assert!(normal_quantile(0.5).abs() < 1e-9);
+        assert!((normal_quantile(0.975) - 1.959963984540054).abs() < 1e-8);
+        assert!((normal_quantile(0.001) + 3.090232306167813).abs() < 1e-8);
+    }
+}
@@ -0,0 +1,445 @@
+//! The N-dimensional Discrete Wavelet Transform, for volumes, video and other gridded data.
+//!
+//! The data is held in row-major order with a _shape_, the length along each axis, so the last
+//! axis is contiguous and every other axis is strided: a 3-D volume of shape `[depth, height,
+//! width]` holds the sample at `(z, y, x)` at position `(z * height + y) * width + x`.
+//!
+//! Each axis is transformed completely in turn, with its own number of levels, which is the
+//! N-dimensional version of the standard decomposition (see [`crate::two_dimensional`]). An axis
+//! with no levels is left alone, so (for example) a space-time cube can be transformed spatially
+//! but not temporally.
+//!
+//! As in 1-D, a level with an odd number of samples along an axis repeats the last one, so the
+//! coefficients can have a larger shape than the data, laid out along each axis the same way as
+//! the 2-D transform lays them out (see [`FilterWavelet2D::coefficients_size`]).
+//!
+//! [`FilterWavelet2D::coefficients_size`]: crate::two_dimensional::FilterWavelet2D::coefficients_size
+use std::marker::PhantomData;
+
+use crate::boundary::BoundaryMode;
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::haar::Haar;
+use crate::two_dimensional::Axis;
+use crate::wavelet;
+
+/// The N-dimensional Discrete Wavelet Transform of a wavelet with compact support.
+///
+/// Each level along an axis transforms the data periodically and halves that axis (rounding up),
+/// so an axis of any length can be transformed by as many levels as the 1-D transform allows.
+#[derive(Debug)]
+pub struct FilterWaveletND<F: Filter> {
+    transform: Vec<f64>,
+    shape: Vec<usize>,
+    levels: Vec<usize>,
+    filter: PhantomData<F>,
+}
+
+/// The N-dimensional Haar transform.
+pub type HaarWaveletND = FilterWaveletND<Haar>;
+
+impl<F: Filter> FilterWaveletND<F> {
+    /// Transform every axis by as many levels as it allows.
+    ///
+    /// This panics if the data doesn't hold as many samples as the shape requires.
+    pub fn transform<T>(data: &[T], shape: &[usize]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect(), shape)
+    }
+
+    /// Transform every axis by as many levels as it allows, in place.
+    ///
+    /// This panics if the data doesn't hold as many samples as the shape requires.
+    pub fn transform_in_place(data: Vec<f64>, shape: &[usize]) -> Self {
+        let levels = Self::max_levels(shape);
+        Self::try_transform_in_place(data, shape, &levels).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Transform each axis by the corresponding number of `levels` (zero to leave it alone), or
+    /// return an error if the data doesn't hold as many finite samples as the shape requires,
+    /// there isn't a level count for every axis, or an axis can't support that many levels.
+    pub fn try_transform<T>(
+        data: &[T],
+        shape: &[usize],
+        levels: &[usize],
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Self::try_transform_in_place(floats, shape, levels)
+    }
+
+    /// Transform each axis by the corresponding number of `levels` in place, or return an error
+    /// as in [`FilterWaveletND::try_transform`].
+    pub fn try_transform_in_place(
+        data: Vec<f64>,
+        shape: &[usize],
+        levels: &[usize],
+    ) -> Result<Self, WaveletError> {
+        let expected = shape.iter().product();
+        if data.len() != expected {
+            return Err(WaveletError::InvalidLength {
+                expected,
+                actual: data.len(),
+            });
+        }
+        if levels.len() != shape.len() {
+            return Err(WaveletError::InvalidLength {
+                expected: shape.len(),
+                actual: levels.len(),
+            });
+        }
+        let bank = F::filter_bank();
+        for (&len, &requested) in shape.iter().zip(levels) {
+            let max = wavelet::max_levels(&bank, len, BoundaryMode::Periodization);
+            if requested > max {
+                return Err(WaveletError::LevelTooDeep { requested, max });
+            }
+        }
+
+        let sizes = coefficients_shape(&bank, shape, levels);
+        let mut coefficients = if sizes == shape {
+            data
+        } else {
+            // the coefficients need more room than the data, so spread it out
+            let mut coefficients = vec![0.0; sizes.iter().product()];
+            let mut samples = data.iter();
+            for_each_offset(&sizes, shape, None, |pos| {
+                coefficients[pos] = *samples.next().unwrap_or(&0.0);
+            });
+            coefficients
+        };
+
+        // the axes already transformed have their coefficients, the rest only their samples
+        let mut extent = shape.to_vec();
+        let mut line = Vec::new();
+        for (axis, &axis_levels) in levels.iter().enumerate() {
+            for_each_line(
+                &mut coefficients,
+                &sizes,
+                &extent,
+                axis,
+                &mut line,
+                |line| wavelet::dwt_in_place(&bank, line, axis_levels),
+            );
+            extent[axis] = sizes[axis];
+        }
+        Ok(FilterWaveletND {
+            transform: coefficients,
+            shape: shape.to_vec(),
+            levels: levels.to_vec(),
+            filter: PhantomData,
+        })
+    }
+
+    /// The most levels that each axis of data with this shape can be transformed by.
+    pub fn max_levels(shape: &[usize]) -> Vec<usize> {
+        let bank = F::filter_bank();
+        shape
+            .iter()
+            .map(|&len| wavelet::max_levels(&bank, len, BoundaryMode::Periodization))
+            .collect()
+    }
+
+    /// Invert the transform, leaving this instance unaffected.
+    pub fn invert(&self) -> Vec<f64> {
+        let mut data = self.transform.clone();
+        self.invert_data(&mut data);
+        data
+    }
+
+    /// Invert the transform in place, moving the underlying memory into the return value.
+    pub fn invert_in_place(&mut self) -> Vec<f64> {
+        let mut data = std::mem::take(&mut self.transform);
+        self.invert_data(&mut data);
+        data
+    }
+
+    fn invert_data(&self, data: &mut Vec<f64>) {
+        let bank = F::filter_bank();
+        let sizes = coefficients_shape(&bank, &self.shape, &self.levels);
+        let mut extent = sizes.clone();
+        let mut line = Vec::new();
+        for axis in (0..self.shape.len()).rev() {
+            let (len, levels) = (self.shape[axis], self.levels[axis]);
+            for_each_line(data, &sizes, &extent, axis, &mut line, |line| {
+                wavelet::inverse_dwt_in_place(&bank, line, len, levels)
+            });
+            extent[axis] = len;
+        }
+
+        // drop the extra coefficients from the ends of the axes, which only moves samples back
+        let mut next = 0;
+        for_each_offset(&sizes, &self.shape, None, |pos| {
+            data[next] = data[pos];
+            next += 1;
+        });
+        data.truncate(next);
+    }
+
+    /// The transform coefficients, in row-major order, with the shape given by
+    /// [`FilterWaveletND::coefficients_shape`].
+    pub fn coefficients(&self) -> &[f64] {
+        &self.transform
+    }
+
+    /// The length of the coefficients along each axis. This is the length of the data, unless a
+    /// level had an odd number of samples along that axis.
+    pub fn coefficients_shape(&self) -> Vec<usize> {
+        coefficients_shape(&F::filter_bank(), &self.shape, &self.levels)
+    }
+
+    /// The length of the data along each axis.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The number of levels each axis was transformed by.
+    pub fn levels(&self) -> &[usize] {
+        &self.levels
+    }
+}
+
+/// The length of the coefficients along each axis of data with this shape.
+fn coefficients_shape(bank: &FilterBank, shape: &[usize], levels: &[usize]) -> Vec<usize> {
+    shape
+        .iter()
+        .zip(levels)
+        .map(|(&len, &levels)| Axis::new(bank, len, levels).size())
+        .collect()
+}
+
+/// Call `f` with the position, in row-major data of shape `grid`, of every index within `extent`
+/// (in row-major order), holding the index along `fixed` (if any) at zero.
+fn for_each_offset<F>(grid: &[usize], extent: &[usize], fixed: Option<usize>, mut f: F)
+where
+    F: FnMut(usize),
+{
+    let free = |axis: usize| Some(axis) != fixed;
+    if (0..grid.len()).any(|axis| free(axis) && extent[axis] == 0) {
+        return;
+    }
+    let mut strides = vec![1; grid.len()];
+    for axis in (0..grid.len().saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * grid[axis + 1];
+    }
+    let mut index = vec![0; grid.len()];
+    let mut pos = 0;
+    'outer: loop {
+        f(pos);
+        // advance the last free axis, carrying into the ones before it
+        for axis in (0..grid.len()).rev().filter(|&axis| free(axis)) {
+            index[axis] += 1;
+            pos += strides[axis];
+            if index[axis] < extent[axis] {
+                continue 'outer;
+            }
+            pos -= index[axis] * strides[axis];
+            index[axis] = 0;
+        }
+        return;
+    }
+}
+
+/// Apply `op` to every line along `axis` of row-major data of shape `grid`, within `extent`.
+///
+/// Each line is the first `extent[axis]` samples along the axis, copied into `line` (which is
+/// reused, so that the only extra memory needed is a single line), passed to `op`, and copied back
+/// however long `op` leaves it.
+fn for_each_line<Op>(
+    data: &mut [f64],
+    grid: &[usize],
+    extent: &[usize],
+    axis: usize,
+    line: &mut Vec<f64>,
+    op: Op,
+) where
+    Op: Fn(&mut Vec<f64>),
+{
+    let len = extent[axis];
+    let stride: usize = grid[axis + 1..].iter().product();
+    if len == 0 {
+        return;
+    }
+    for_each_offset(grid, extent, Some(axis), |start| {
+        line.clear();
+        line.extend((0..len).map(|k| data[start + k * stride]));
+        op(line);
+        for (k, v) in line.iter().enumerate() {
+            data[start + k * stride] = *v;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daubechies::Daub4;
+    use crate::two_dimensional::{Decomposition, FilterWavelet2D};
+
+    fn volume(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| ((i * 37) % 23) as f64 - 11.0 + (i % 5) as f64 * 0.25)
+            .collect()
+    }
+
+    #[test]
+    fn inverts() {
+        let shape = [4, 8, 2, 16];
+        let data = volume(shape.iter().product());
+        for levels in [vec![2, 3, 1, 4], vec![1, 0, 1, 2], vec![0, 0, 0, 0]] {
+            let mut wavelet = FilterWaveletND::<Daub4>::try_transform(&data, &shape, &levels)
+                .expect("Transform failed");
+            let inverse = wavelet.invert_in_place();
+            for (pos, expected) in data.iter().enumerate() {
+                assert!(
+                    (expected - inverse[pos]).abs() < 1e-12,
+                    "Element at pos {} incorrect with levels {:?}. Expected {} but was {}",
+                    pos,
+                    levels,
+                    expected,
+                    inverse[pos]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_standard_2d_decomposition() {
+        let (width, height) = (16, 8);
+        let data = volume(width * height);
+        let nd = FilterWaveletND::<Daub4>::try_transform(&data, &[height, width], &[3, 3]).unwrap();
+        let two_d =
+            FilterWavelet2D::<Daub4>::transform_with(&data, width, height, Decomposition::Standard);
+
+        for (pos, (expected, actual)) in two_d
+            .coefficients()
+            .iter()
+            .zip(nd.coefficients())
+            .enumerate()
+        {
+            assert!(
+                (expected - actual).abs() < 1e-12,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn transforms_any_shape() {
+        let shape = [5, 7, 12];
+        let data = volume(shape.iter().product());
+        assert_eq!(vec![3, 3, 4], HaarWaveletND::max_levels(&shape));
+        for levels in [vec![3, 3, 4], vec![1, 2, 0], vec![0, 3, 1]] {
+            let mut wavelet = FilterWaveletND::<Daub4>::try_transform(&data, &shape, &levels)
+                .expect("Transform failed");
+            let sizes = wavelet.coefficients_shape();
+            assert_eq!(
+                sizes.iter().product::<usize>(),
+                wavelet.coefficients().len()
+            );
+            let inverse = wavelet.invert_in_place();
+            assert_eq!(data.len(), inverse.len(), "Inverse has the wrong length!");
+            for (pos, expected) in data.iter().enumerate() {
+                assert!(
+                    (expected - inverse[pos]).abs() < 1e-12,
+                    "Element at pos {} incorrect with levels {:?}. Expected {} but was {}",
+                    pos,
+                    levels,
+                    expected,
+                    inverse[pos]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_standard_2d_decomposition_of_any_dimensions() {
+        let (width, height) = (12, 7);
+        let data = volume(width * height);
+        let levels = FilterWavelet2D::<Daub4>::max_levels(width, height);
+        let nd =
+            FilterWaveletND::<Daub4>::try_transform(&data, &[height, width], &[levels; 2]).unwrap();
+        let two_d =
+            FilterWavelet2D::<Daub4>::transform_with(&data, width, height, Decomposition::Standard);
+        let (w, h) = two_d.coefficients_size();
+        assert_eq!(vec![h, w], nd.coefficients_shape());
+        for (pos, (expected, actual)) in two_d
+            .coefficients()
+            .iter()
+            .zip(nd.coefficients())
+            .enumerate()
+        {
+            assert!(
+                (expected - actual).abs() < 1e-12,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn skipped_axes_are_untouched() {
+        // transforming the spatial axes of a [time, height, width] cube transforms each frame
+        let shape = [3, 4, 8];
+        let data = volume(shape.iter().product());
+        let wavelet = HaarWaveletND::try_transform(&data, &shape, &[0, 2, 2]).unwrap();
+
+        for (t, frame) in data.chunks(32).enumerate() {
+            let expected = FilterWavelet2D::<Haar>::try_transform_with(
+                frame,
+                8,
+                4,
+                2,
+                Decomposition::Standard,
+            )
+            .unwrap();
+            let actual = &wavelet.coefficients()[t * 32..(t + 1) * 32];
+            for (pos, (e, a)) in expected.coefficients().iter().zip(actual).enumerate() {
+                assert!(
+                    (e - a).abs() < 1e-12,
+                    "Frame {} coefficient {} incorrect. Expected {} but was {}",
+                    t,
+                    pos,
+                    e,
+                    a
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reports_invalid_shapes() {
+        assert_eq!(vec![2, 2, 6], HaarWaveletND::max_levels(&[4, 3, 48]));
+        assert_eq!(
+            WaveletError::InvalidLength {
+                expected: 24,
+                actual: 20
+            },
+            HaarWaveletND::try_transform(&[0.0; 20], &[2, 3, 4], &[0, 0, 0]).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::InvalidLength {
+                expected: 3,
+                actual: 2
+            },
+            HaarWaveletND::try_transform(&[0.0; 24], &[2, 3, 4], &[1, 0]).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::LevelTooDeep {
+                requested: 3,
+                max: 2
+            },
+            HaarWaveletND::try_transform(&[0.0; 24], &[2, 3, 4], &[1, 3, 2]).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Wavelet packets, and the search for the best basis.
+//!
+//! The Discrete Wavelet Transform splits the data into averages and differences, and then splits
+//! only the averages again at every level. A wavelet packet decomposition splits the differences
+//! too, so each level holds `2^j` nodes, forming a complete binary tree:
+//!
+//! ```text
+//!                   (0,0)
+//!           (1,0)             (1,1)
+//!       (2,0)   (2,1)     (2,2)   (2,3)
+//! ```
+//!
+//! Each node is addressed by its `(level, position)`, or by the path of averages (`a`) and
+//! differences (`d`) which leads to it from the root, so `(2,1)` is `"ad"`, the differences of the
+//! averages. The children of `(j,p)` are `(j+1,2p)`, its averages, and `(j+1,2p+1)`, its
+//! differences.
+//!
+//! Any set of nodes which covers the root exactly once (so that, going down any branch of the
+//! tree, exactly one node is in the set) is a basis: the data can be reconstructed from those
+//! nodes alone. The ordinary transform is one such basis, but there are many others, and the
+//! Coifman-Wickerhauser search ([`WaveletPacket::best_basis`]) finds the one which minimizes an
+//! additive cost function, usually to find the most compact representation of the data.
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use crate::boundary::BoundaryMode;
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::haar::Haar;
+use crate::wavelet;
+
+/// The position of a node in a wavelet packet tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Node {
+    /// The level of the node, where the root (the data itself) is level 0.
+    pub level: usize,
+    /// The position of the node within its level, from 0 to `2^level - 1`.
+    pub position: usize,
+}
+
+impl Node {
+    /// The root of the tree, which holds the data itself.
+    pub fn root() -> Node {
+        Node {
+            level: 0,
+            position: 0,
+        }
+    }
+
+    /// The node at the end of a path of averages (`a`) and differences (`d`) from the root, or
+    /// `None` if the path has any other characters, or its position doesn't fit in a `usize`.
+    pub fn from_path(path: &str) -> Option<Node> {
+        path.chars().try_fold(Node::root(), |node, step| {
+            let bit = match step {
+                'a' => 0,
+                'd' => 1,
+                _ => return None,
+            };
+            Some(Node {
+                level: node.level + 1,
+                position: node.position.checked_mul(2)? + bit,
+            })
+        })
+    }
+
+    /// The path of averages (`a`) and differences (`d`) from the root to this node.
+    pub fn path(&self) -> String {
+        (0..self.level)
+            .rev()
+            .map(|bit| {
+                // past the width of a `usize`, every step of the path is an average
+                let shifted = u32::try_from(bit)
+                    .ok()
+                    .and_then(|bit| self.position.checked_shr(bit));
+                if shifted.unwrap_or(0) & 1 == 0 {
+                    'a'
+                } else {
+                    'd'
+                }
+            })
+            .collect()
+    }
+
+    /// The averages and differences of this node.
+    pub fn children(&self) -> (Node, Node) {
+        let child = |position| Node {
+            level: self.level + 1,
+            position,
+        };
+        (child(2 * self.position), child(2 * self.position + 1))
+    }
+}
+
+/// An additive cost function, for choosing the best basis.
+///
+/// Each cost is a sum over the coefficients of a node, so the cost of a basis is the sum of the
+/// costs of its nodes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cost {
+    /// The Shannon entropy `-Σ p log p` of the energy distribution `p = x²/‖X‖²`, where `X` is
+    /// the data. This is low when the energy is concentrated in a few coefficients.
+    Shannon,
+    /// The "log energy" entropy `Σ log x²`, skipping zeros.
+    LogEnergy,
+    /// The number of coefficients whose magnitude exceeds the threshold.
+    Threshold(f64),
+    /// The `p`th power of the `L^p` norm, `Σ |x|^p`. For `p < 2` this favours sparse nodes.
+    Norm(f64),
+}
+
+impl Cost {
+    fn of(&self, coefficients: &[f64], energy: f64) -> f64 {
+        match *self {
+            Cost::Shannon => coefficients
+                .iter()
+                .map(|x| x * x / energy)
+                .filter(|p| *p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum(),
+            Cost::LogEnergy => coefficients
+                .iter()
+                .map(|x| x * x)
+                .filter(|e| *e > 0.0)
+                .map(f64::ln)
+                .sum(),
+            Cost::Threshold(threshold) => {
+                coefficients.iter().filter(|x| x.abs() > threshold).count() as f64
+            }
+            Cost::Norm(p) => coefficients.iter().map(|x| x.abs().powf(p)).sum(),
+        }
+    }
+}
+
+/// A wavelet packet decomposition of data with a wavelet of compact support.
+///
+/// Each node is split periodically (see [`BoundaryMode::Periodization`]), so the data can have any
+/// length, and every node at level `j` has the same length, `ceil(N/2^j)` (give or take the
+/// rounding at each level).
+#[derive(Debug)]
+pub struct WaveletPacket<F: Filter> {
+    levels: Vec<Vec<Vec<f64>>>,
+    energy: f64,
+    filter: PhantomData<F>,
+}
+
+/// The Haar wavelet packet decomposition.
+pub type HaarPacket = WaveletPacket<Haar>;
+
+impl<F: Filter> WaveletPacket<F> {
+    /// Decompose the data into as many levels as it can support.
+    pub fn decompose<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let levels = Self::max_levels(data.len());
+        Self::decompose_levels(data.iter().map(|v| (*v).into()).collect(), levels)
+    }
+
+    /// Decompose the data into `levels` levels, or return an error if the data isn't finite, or
+    /// can't support that many levels.
+    pub fn try_decompose_with<T>(data: &[T], levels: usize) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let max = Self::max_levels(data.len());
+        if levels > max {
+            return Err(WaveletError::LevelTooDeep {
+                requested: levels,
+                max,
+            });
+        }
+        Ok(Self::decompose_levels(
+            data.iter().map(|v| (*v).into()).collect(),
+            levels,
+        ))
+    }
+
+    /// The most levels that `len` samples can be decomposed into.
+    pub fn max_levels(len: usize) -> usize {
+        wavelet::max_levels(&F::filter_bank(), len, BoundaryMode::Periodization)
+    }
+
+    fn decompose_levels(data: Vec<f64>, levels: usize) -> Self {
+        let bank = F::filter_bank();
+        let energy = data.iter().map(|x| x * x).sum();
+        let mut tree = vec![vec![data]];
+        for level in 0..levels {
+            let next = tree[level]
+                .iter()
+                .flat_map(|node| {
+                    let mut avg = wavelet::dwt(&bank, node, BoundaryMode::Periodization, 1);
+                    let diff = avg.split_off(avg.len() / 2);
+                    [avg, diff]
+                })
+                .collect();
+            tree.push(next);
+        }
+        WaveletPacket {
+            levels: tree,
+            energy,
+            filter: PhantomData,
+        }
+    }
+
+    /// The number of levels below the root.
+    pub fn levels(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The coefficients of a node, or `None` if it isn't in the tree.
+    pub fn node(&self, node: Node) -> Option<&[f64]> {
+        self.levels
+            .get(node.level)?
+            .get(node.position)
+            .map(|n| &n[..])
+    }
+
+    /// The coefficients of a node, mutably, or `None` if it isn't in the tree.
+    ///
+    /// Changes are used by [`WaveletPacket::reconstruct`] (for example, to denoise a basis), but
+    /// aren't propagated to the rest of the tree.
+    pub fn node_mut(&mut self, node: Node) -> Option<&mut [f64]> {
+        self.levels
+            .get_mut(node.level)?
+            .get_mut(node.position)
+            .map(|n| &mut n[..])
+    }
+
+    /// The coefficients of the node at the end of a path of averages (`a`) and differences (`d`),
+    /// or `None` if there's no such node.
+    pub fn node_at_path(&self, path: &str) -> Option<&[f64]> {
+        self.node(Node::from_path(path)?)
+    }
+
+    /// Every node at `level`, in order of position.
+    pub fn level(&self, level: usize) -> Vec<Node> {
+        let count = if level <= self.levels() {
+            1 << level
+        } else {
+            0
+        };
+        (0..count)
+            .map(|position| Node { level, position })
+            .collect()
+    }
+
+    /// Reconstruct the data from the given nodes, or return an error if they don't form a basis.
+    pub fn reconstruct(&self, basis: &[Node]) -> Result<Vec<f64>, WaveletError> {
+        let basis: HashSet<Node> = basis.iter().copied().collect();
+        let mut used = 0;
+        let data = self
+            .reconstruct_node(&F::filter_bank(), Node::root(), &basis, &mut used)
+            .ok_or(WaveletError::InvalidBasis)?;
+        // a node below another in the basis would never have been reached
+        if used != basis.len() {
+            return Err(WaveletError::InvalidBasis);
+        }
+        Ok(data)
+    }
+
+    fn reconstruct_node(
+        &self,
+        bank: &FilterBank,
+        node: Node,
+        basis: &HashSet<Node>,
+        used: &mut usize,
+    ) -> Option<Vec<f64>> {
+        if basis.contains(&node) {
+            *used += 1;
+            return Some(self.node(node)?.to_vec());
+        }
+        if node.level >= self.levels() {
+            return None;
+        }
+        let (avg, diff) = node.children();
+        let mut coefficients = self.reconstruct_node(bank, avg, basis, used)?;
+        coefficients.extend(self.reconstruct_node(bank, diff, basis, used)?);
+        let len = self.levels[node.level][node.position].len();
+        Some(wavelet::inverse_dwt(
+            bank,
+            &coefficients,
+            len,
+            BoundaryMode::Periodization,
+            1,
+        ))
+    }
+
+    /// The basis which minimizes the cost function, in order of level and position.
+    ///
+    /// Working up from the deepest level, each node is kept if it costs no more than the best
+    /// bases of its children do together, and replaced by them otherwise.
+    pub fn best_basis(&self, cost: Cost) -> Vec<Node> {
+        let (mut basis, _) = self.best_basis_below(Node::root(), cost);
+        basis.sort();
+        basis
+    }
+
+    fn best_basis_below(&self, node: Node, cost: Cost) -> (Vec<Node>, f64) {
+        let own = cost.of(&self.levels[node.level][node.position], self.energy);
+        if node.level == self.levels() {
+            return (vec![node], own);
+        }
+        let (avg, diff) = node.children();
+        let (mut basis, avg_cost) = self.best_basis_below(avg, cost);
+        let (diff_basis, diff_cost) = self.best_basis_below(diff, cost);
+        if own <= avg_cost + diff_cost {
+            return (vec![node], own);
+        }
+        basis.extend(diff_basis);
+        (basis, avg_cost + diff_cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daubechies::Daub4;
+    use crate::test_util::{assert_close, DATA};
+
+    #[test]
+    fn nodes_are_addressed_by_path() {
+        let node = Node::from_path("dad").unwrap();
+        assert_eq!(
+            Node {
+                level: 3,
+                position: 5
+            },
+            node
+        );
+        assert_eq!("dad", node.path());
+        assert_eq!(Some(Node::root()), Node::from_path(""));
+        assert_eq!(None, Node::from_path("ax"));
+
+        // positions are only addressable while they fit in a `usize`
+        let bits = usize::BITS as usize;
+        let deep = Node::from_path(&"a".repeat(bits + 10)).unwrap();
+        assert_eq!(bits + 10, deep.level);
+        assert_eq!(0, deep.position);
+        assert_eq!("a".repeat(bits + 10), deep.path());
+        let last = format!("{}{}", "a".repeat(bits + 9), "d");
+        assert_eq!(last, Node::from_path(&last).unwrap().path());
+        assert!(Node::from_path(&"d".repeat(bits)).is_some());
+        assert_eq!(None, Node::from_path(&"d".repeat(bits + 1)));
+        assert_eq!(None, Node::from_path(&format!("d{}", "a".repeat(bits))));
+
+        let packet = HaarPacket::try_decompose_with(&[1.0, 3.0, 5.0, 11.0], 2).unwrap();
+        // averages are (4, 16)/√2, differences (-2, -6)/√2; their differences are -12/2 and 4/2
+        assert_close(&[-6.0], packet.node_at_path("ad").unwrap(), "ad");
+        assert_close(&[2.0], packet.node_at_path("dd").unwrap(), "dd");
+    }
+
+    #[test]
+    fn reconstructs_from_any_basis() {
+        let packet = WaveletPacket::<Daub4>::try_decompose_with(&DATA, 3).unwrap();
+        let bases = [
+            vec![Node::root()],
+            packet.level(3),
+            ["a", "da", "dd"]
+                .map(|p| Node::from_path(p).unwrap())
+                .to_vec(),
+            ["aaa", "aad", "ad", "d"]
+                .map(|p| Node::from_path(p).unwrap())
+                .to_vec(),
+        ];
+        for basis in bases {
+            let data = packet.reconstruct(&basis).expect("Should be a basis");
+            assert_close(&DATA, &data, &format!("{:?}", basis));
+        }
+    }
+
+    #[test]
+    fn rejects_inadmissible_bases() {
+        let packet = HaarPacket::try_decompose_with(&DATA, 3).unwrap();
+        for paths in [vec!["a"], vec!["a", "d", "dd"], vec!["a", "aa", "d"]] {
+            let basis: Vec<Node> = paths.iter().map(|p| Node::from_path(p).unwrap()).collect();
+            assert_eq!(
+                Err(WaveletError::InvalidBasis),
+                packet.reconstruct(&basis),
+                "{:?}",
+                paths
+            );
+        }
+    }
+
+    #[test]
+    fn best_basis_finds_oscillations() {
+        // a pure high-frequency oscillation lives in the differences, so splitting those is best
+        let data: Vec<f64> = (0..32).map(|i| [1.0, 1.0, -1.0, -1.0][i % 4]).collect();
+        let packet = HaarPacket::decompose(&data);
+
+        for cost in [Cost::Shannon, Cost::Threshold(1e-9), Cost::Norm(1.0)] {
+            let basis = packet.best_basis(cost);
+            assert_close(&data, &packet.reconstruct(&basis).unwrap(), "Best basis");
+            let nonzero: usize = basis
+                .iter()
+                .map(|node| {
+                    let coefficients = packet.node(*node).unwrap();
+                    coefficients.iter().filter(|x| x.abs() > 1e-9).count()
+                })
+                .sum();
+            assert_eq!(1, nonzero, "{:?} should concentrate the signal", cost);
+        }
+    }
+
+    #[test]
+    fn log_energy_prefers_small_coefficients() {
+        let from_paths = |paths: &[&str]| -> Vec<Node> {
+            paths.iter().map(|p| Node::from_path(p).unwrap()).collect()
+        };
+
+        // averaging a constant gives coefficients twice as large at each level, so when they're
+        // already larger than 1 it's cheapest to concentrate them in the deepest average
+        let packet = HaarPacket::try_decompose_with(&[2.0; 4], 2).unwrap();
+        assert_eq!(
+            from_paths(&["d", "aa", "ad"]),
+            packet.best_basis(Cost::LogEnergy)
+        );
+
+        // but when they're smaller than 1, each one lowers the cost, so the data is best
+        let packet = HaarPacket::try_decompose_with(&[0.5; 4], 2).unwrap();
+        assert_eq!(vec![Node::root()], packet.best_basis(Cost::LogEnergy));
+    }
+}
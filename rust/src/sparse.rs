@@ -0,0 +1,415 @@
+//! Sparse Haar transforms, which store only their non-zero coefficients.
+//!
+//! After thresholding (see [`crate::denoising`] and [`crate::compression`]), most of the
+//! coefficients of a transform are zero, so it's much cheaper to keep just the rest as
+//! `(position, value)` pairs, along with the length of the transform they came from. That's what
+//! makes a wavelet synopsis smaller than the data it summarises, so the operations on it avoid
+//! building the dense coefficients again unless they're asked for.
+//!
+//! This relies on the structure of the Haar wavelet: the coefficient at position `p` of level `j`
+//! only affects the samples `[p 2^j, (p+1) 2^j)` (the error tree of `notes/operations.md`), adding
+//! `2^(-j/2)` times its value to the first half of them and subtracting it from the second half.
+use crate::boundary::BoundaryMode;
+use crate::compression::CompressedWavelet;
+use crate::error::WaveletError;
+use crate::filter::Filter;
+use crate::haar::{self, Haar, HaarWavelet};
+use crate::wavelet::{self, FilterWavelet};
+use crate::DetailCoefficients;
+
+/// A Haar transform (with [`BoundaryMode::Periodization`]) holding only its non-zero coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseWavelet {
+    coefficients: Vec<(usize, f64)>,
+    len: usize,
+    levels: usize,
+}
+
+impl SparseWavelet {
+    /// Build a sparse transform of `len` samples with `levels` levels from its non-zero
+    /// coefficients, as `(position, value)` pairs in the layout `[avg | d^J | ... | d^1]`.
+    ///
+    /// The pairs can be in any order, and any with the same position are added together. This
+    /// returns an error if `len` samples can't support that many levels, or if a position is
+    /// beyond the end of the transform.
+    pub fn try_from_coefficients<I>(
+        coefficients: I,
+        len: usize,
+        levels: usize,
+    ) -> Result<SparseWavelet, WaveletError>
+    where
+        I: IntoIterator<Item = (usize, f64)>,
+    {
+        let max = HaarWavelet::max_levels(len, BoundaryMode::Periodization);
+        if levels > max {
+            return Err(WaveletError::LevelTooDeep {
+                requested: levels,
+                max,
+            });
+        }
+        let mut sparse = SparseWavelet {
+            coefficients: coefficients.into_iter().collect(),
+            len,
+            levels,
+        };
+        let dense_len = sparse.dense_len();
+        if let Some(&(pos, _)) = sparse
+            .coefficients
+            .iter()
+            .find(|(pos, _)| *pos >= dense_len)
+        {
+            return Err(WaveletError::InvalidLength {
+                expected: dense_len,
+                actual: pos + 1,
+            });
+        }
+        sparse.coefficients.sort_by_key(|&(pos, _)| pos);
+        sparse.coefficients = combine(sparse.coefficients.into_iter());
+        Ok(sparse)
+    }
+
+    /// The non-zero coefficients, as `(position, value)` in order of position.
+    pub fn coefficients(&self) -> &[(usize, f64)] {
+        &self.coefficients
+    }
+
+    /// The number of samples the transform was computed from.
+    pub fn signal_len(&self) -> usize {
+        self.len
+    }
+
+    /// The number of coefficients in the dense transform, including the zeros.
+    pub fn dense_len(&self) -> usize {
+        let lengths = self.level_lengths();
+        lengths[1..].iter().sum::<usize>() + lengths[self.levels]
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// The dense transform, with the zeros filled back in.
+    pub fn to_dense(&self) -> HaarWavelet {
+        let mut dense = vec![0.0; self.dense_len()];
+        for &(pos, value) in &self.coefficients {
+            dense[pos] = value;
+        }
+        FilterWavelet::try_from_coefficients(
+            dense,
+            self.len,
+            BoundaryMode::Periodization,
+            self.levels,
+        )
+        .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Reconstruct the data, in `O(N + K)` time for `K` non-zero coefficients.
+    ///
+    /// Each coefficient adds a step to a difference array at the start, middle and end of its
+    /// support, and the data is the running sum of the steps.
+    pub fn invert(&self) -> Vec<f64> {
+        let mut steps = vec![0.0; self.len + 1];
+        let lengths = self.level_lengths();
+        for &(pos, value) in &self.coefficients {
+            let (level, index, average) = locate(&lengths, pos);
+            let width = 1usize << level;
+            let weight = value * std::f64::consts::FRAC_1_SQRT_2.powi(level as i32);
+            let start = (index * width).min(self.len);
+            let end = ((index + 1) * width).min(self.len);
+            steps[start] += weight;
+            if average {
+                steps[end] -= weight;
+            } else {
+                let middle = (index * width + width / 2).min(self.len);
+                steps[middle] -= 2.0 * weight;
+                steps[end] += weight;
+            }
+        }
+        let mut sum = 0.0;
+        steps
+            .iter()
+            .take(self.len)
+            .map(|step| {
+                sum += step;
+                sum
+            })
+            .collect()
+    }
+
+    /// Reconstruct the sample at `index` alone, or `None` if it's beyond the end of the data.
+    ///
+    /// Only one coefficient at each level affects any sample, so this looks up `levels + 1`
+    /// coefficients, each by binary search.
+    pub fn value_at(&self, index: usize) -> Option<f64> {
+        if index >= self.len {
+            return None;
+        }
+        let lengths = self.level_lengths();
+        let lookup = |pos: usize| {
+            self.coefficients
+                .binary_search_by_key(&pos, |&(p, _)| p)
+                .map_or(0.0, |i| self.coefficients[i].1)
+        };
+        Some(haar::point_value(&lengths, index, lookup))
+    }
+
+    /// Merge another sparse transform of the same number of samples and levels into this one.
+    ///
+    /// The transform is linear, so this is the sparse transform of the sum of the two signals
+    /// (for example, of the counts in two partitions of a data set). The merge walks through the
+    /// two sets of coefficients together, so takes time proportional to the number of them. This
+    /// returns an error if the two transforms don't have the same shape.
+    pub fn merge(&self, other: &SparseWavelet) -> Result<SparseWavelet, WaveletError> {
+        if self.len != other.len {
+            return Err(WaveletError::InvalidLength {
+                expected: self.len,
+                actual: other.len,
+            });
+        }
+        if self.levels != other.levels {
+            return Err(WaveletError::LevelMismatch {
+                expected: self.levels,
+                actual: other.levels,
+            });
+        }
+
+        let mut merged = Vec::with_capacity(self.coefficients.len() + other.coefficients.len());
+        let (mut a, mut b) = (
+            self.coefficients.iter().peekable(),
+            other.coefficients.iter().peekable(),
+        );
+        while let (Some(&&x), Some(&&y)) = (a.peek(), b.peek()) {
+            if x.0 <= y.0 {
+                merged.push(x);
+                a.next();
+            } else {
+                merged.push(y);
+                b.next();
+            }
+        }
+        merged.extend(a);
+        merged.extend(b);
+
+        Ok(SparseWavelet {
+            coefficients: combine(merged.into_iter()),
+            len: self.len,
+            levels: self.levels,
+        })
+    }
+
+    fn level_lengths(&self) -> Vec<usize> {
+        wavelet::level_lengths(
+            &Haar::filter_bank(),
+            self.len,
+            BoundaryMode::Periodization,
+            self.levels,
+        )
+    }
+}
+
+impl TryFrom<&HaarWavelet> for SparseWavelet {
+    type Error = WaveletError;
+
+    /// The non-zero coefficients of a dense transform.
+    ///
+    /// The transform must use [`BoundaryMode::Periodization`] (which is the default), otherwise
+    /// this returns an error.
+    fn try_from(dense: &HaarWavelet) -> Result<SparseWavelet, WaveletError> {
+        check_mode(dense.mode())?;
+        Ok(SparseWavelet {
+            coefficients: dense
+                .coefficients()
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c != 0.0)
+                .map(|(pos, c)| (pos, *c))
+                .collect(),
+            len: dense.signal_len(),
+            levels: dense.levels(),
+        })
+    }
+}
+
+impl TryFrom<&CompressedWavelet<Haar>> for SparseWavelet {
+    type Error = WaveletError;
+
+    /// The coefficients kept by compressing a transform, without building the dense transform.
+    ///
+    /// The transform must use [`BoundaryMode::Periodization`] (which is the default), otherwise
+    /// this returns an error.
+    fn try_from(compressed: &CompressedWavelet<Haar>) -> Result<SparseWavelet, WaveletError> {
+        check_mode(compressed.mode())?;
+        Ok(SparseWavelet {
+            coefficients: compressed
+                .coefficients()
+                .iter()
+                .filter(|(_, c)| *c != 0.0)
+                .cloned()
+                .collect(),
+            len: compressed.signal_len(),
+            levels: compressed.levels(),
+        })
+    }
+}
+
+/// Check that a transform uses the only boundary mode a sparse transform supports.
+fn check_mode(mode: BoundaryMode) -> Result<(), WaveletError> {
+    match mode {
+        BoundaryMode::Periodization => Ok(()),
+        mode => Err(WaveletError::UnsupportedMode { mode }),
+    }
+}
+
+/// Add together the values of adjacent coefficients (in sorted order) with the same position,
+/// dropping any which come to zero.
+fn combine<I: Iterator<Item = (usize, f64)>>(sorted: I) -> Vec<(usize, f64)> {
+    let mut combined: Vec<(usize, f64)> = Vec::new();
+    for (pos, value) in sorted {
+        match combined.last_mut() {
+            Some(last) if last.0 == pos => last.1 += value,
+            _ => combined.push((pos, value)),
+        }
+    }
+    combined.retain(|(_, value)| *value != 0.0);
+    combined
+}
+
+/// Find the coefficient at position `pos` of the layout `[avg | d^J | ... | d^1]` (whose levels
+/// have the given lengths), as its level, its index within the level, and whether it's one of the
+/// final averages.
+pub(crate) fn locate(lengths: &[usize], pos: usize) -> (usize, usize, bool) {
+    let levels = lengths.len() - 1;
+    if pos < lengths[levels] {
+        return (levels, pos, true);
+    }
+    let mut start = lengths[levels];
+    for level in (1..=levels).rev() {
+        if pos < start + lengths[level] {
+            return (level, pos - start, false);
+        }
+        start += lengths[level];
+    }
+    panic!("Position {} is beyond the end of the transform", pos);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Budget;
+    use crate::test_util::{assert_close, DATA};
+    use crate::WaveletTransform;
+
+    #[test]
+    fn inverts_like_the_dense_transform() {
+        for len in [1, 2, 7, 8, 13] {
+            let dense = HaarWavelet::transform(&DATA[..len]);
+            let sparse = SparseWavelet::try_from(&dense).unwrap();
+            let message = format!("Length {}", len);
+            assert_close(&dense.invert(), &sparse.invert(), &message);
+            assert_close(
+                dense.coefficients(),
+                sparse.to_dense().coefficients(),
+                &message,
+            );
+            let values: Vec<f64> = (0..len).map(|i| sparse.value_at(i).unwrap()).collect();
+            assert_close(&DATA[..len], &values, &message);
+            assert_eq!(None, sparse.value_at(len));
+
+            // with fewer levels
+            let levels = HaarWavelet::max_levels(len, BoundaryMode::Periodization) / 2;
+            let dense =
+                HaarWavelet::try_transform_with(&DATA[..len], BoundaryMode::Periodization, levels)
+                    .unwrap();
+            assert_close(
+                &DATA[..len],
+                &SparseWavelet::try_from(&dense).unwrap().invert(),
+                &format!("Length {} with {} levels", len, levels),
+            );
+        }
+    }
+
+    #[test]
+    fn inverts_compressed_transforms() {
+        let compressed = HaarWavelet::transform(&DATA).compress(Budget::Largest(4));
+        let sparse = SparseWavelet::try_from(&compressed).unwrap();
+        assert_eq!(4, sparse.coefficients().len());
+        let expected = compressed.invert();
+        assert_close(&expected, &sparse.invert(), "Compressed");
+        for (i, e) in expected.iter().enumerate() {
+            assert!((e - sparse.value_at(i).unwrap()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn merges_transforms() {
+        let other: Vec<f64> = DATA.iter().map(|v| 10.0 - v).collect();
+        let a = SparseWavelet::try_from(&HaarWavelet::transform(&DATA)).unwrap();
+        let b = SparseWavelet::try_from(&HaarWavelet::transform(&other)).unwrap();
+        let merged = a.merge(&b).unwrap();
+        assert_close(&[10.0; 13], &merged.invert(), "Merged");
+        // a constant only has its final average (up to rounding)
+        assert!(merged.coefficients()[1..].iter().all(|c| c.1.abs() < 1e-12));
+
+        let short = SparseWavelet::try_from(&HaarWavelet::transform(&DATA[..8])).unwrap();
+        assert_eq!(
+            Err(WaveletError::InvalidLength {
+                expected: 13,
+                actual: 8
+            }),
+            a.merge(&short)
+        );
+
+        let shallow = HaarWavelet::try_transform_with(&DATA, BoundaryMode::Periodization, 2)
+            .and_then(|dense| SparseWavelet::try_from(&dense))
+            .unwrap();
+        assert_eq!(
+            Err(WaveletError::LevelMismatch {
+                expected: 4,
+                actual: 2
+            }),
+            a.merge(&shallow)
+        );
+    }
+
+    #[test]
+    fn requires_periodization() {
+        let dense = HaarWavelet::try_transform_with(&DATA, BoundaryMode::Zero, 2).unwrap();
+        assert_eq!(
+            Err(WaveletError::UnsupportedMode {
+                mode: BoundaryMode::Zero
+            }),
+            SparseWavelet::try_from(&dense)
+        );
+        assert_eq!(
+            Err(WaveletError::UnsupportedMode {
+                mode: BoundaryMode::Zero
+            }),
+            SparseWavelet::try_from(&dense.compress(Budget::Largest(4)))
+        );
+    }
+
+    #[test]
+    fn builds_from_coefficients() {
+        // duplicates are added together
+        let sparse =
+            SparseWavelet::try_from_coefficients(vec![(3, 1.0), (0, 2.0), (3, 0.5)], 8, 3).unwrap();
+        assert_eq!(&[(0, 2.0), (3, 1.5)], sparse.coefficients());
+        assert_eq!(8, sparse.dense_len());
+
+        assert_eq!(
+            Err(WaveletError::InvalidLength {
+                expected: 8,
+                actual: 9
+            }),
+            SparseWavelet::try_from_coefficients(vec![(8, 1.0)], 8, 3)
+        );
+        assert_eq!(
+            Err(WaveletError::LevelTooDeep {
+                requested: 4,
+                max: 3
+            }),
+            SparseWavelet::try_from_coefficients(vec![], 8, 4)
+        );
+    }
+}
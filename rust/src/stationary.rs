@@ -0,0 +1,290 @@
+//! The Stationary (or undecimated) Wavelet Transform.
+//!
+//! The decimated transform keeps only every other average and difference at each level, which is
+//! what makes it non-redundant, but also what makes it _shift-variant_: shifting the signal by a
+//! single sample can change the coefficients completely. The stationary transform keeps all `N`
+//! averages and differences at every level instead, so shifting the signal just shifts the
+//! coefficients.
+//!
+//! Rather than decimating the data, each level upsamples the filters, inserting `2^(j-1) - 1`
+//! zeros between their coefficients at level `j` (the _à trous_, or "with holes", algorithm). The
+//! even samples of the first level are exactly the decimated transform, and the odd samples are
+//! the decimated transform of the signal shifted by one, so the inverse has two reconstructions
+//! of every level available, and averages them.
+use std::marker::PhantomData;
+
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::haar::Haar;
+use crate::DetailCoefficients;
+
+/// The Stationary Wavelet Transform of a wavelet with compact support.
+///
+/// The data is extended periodically, so it can have any length. The transform holds the
+/// differences at each level, and the averages at the coarsest level, for `(levels + 1) * N`
+/// coefficients in all.
+#[derive(Debug)]
+pub struct StationaryWavelet<F: Filter> {
+    approximation: Vec<f64>,
+    details: Vec<Vec<f64>>,
+    filter: PhantomData<F>,
+}
+
+/// The Stationary Haar transform.
+pub type HaarStationaryWavelet = StationaryWavelet<Haar>;
+
+impl<F: Filter> StationaryWavelet<F> {
+    /// Perform `levels` levels of the transform, or return an error if the data isn't finite, or
+    /// can't support that many levels.
+    pub fn try_transform_with<T>(data: &[T], levels: usize) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let max = Self::max_levels(data.len());
+        if levels > max {
+            return Err(WaveletError::LevelTooDeep {
+                requested: levels,
+                max,
+            });
+        }
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Ok(Self::transform_levels(floats, levels))
+    }
+
+    /// The most levels of the transform that `len` samples can support.
+    ///
+    /// This is the number of levels before the upsampled filters are spread further apart than
+    /// the data is long.
+    pub fn max_levels(len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+        len.ilog2() as usize
+    }
+
+    fn transform_levels(mut approximation: Vec<f64>, levels: usize) -> Self {
+        let bank = F::filter_bank();
+        let mut details = Vec::with_capacity(levels);
+        let mut next = vec![0.0; approximation.len()];
+        for level in 0..levels {
+            let mut detail = vec![0.0; approximation.len()];
+            level_swt(&bank, &approximation, 1 << level, &mut next, &mut detail);
+            std::mem::swap(&mut approximation, &mut next);
+            details.push(detail);
+        }
+        StationaryWavelet {
+            approximation,
+            details,
+            filter: PhantomData,
+        }
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.details.len()
+    }
+
+    /// The differences at `level`, where level 1 is the finest, or `None` if there's no such level.
+    pub fn detail(&self, level: usize) -> Option<&[f64]> {
+        self.details.get(level.checked_sub(1)?).map(|d| &d[..])
+    }
+
+    /// The averages at `level`, where level 1 is the finest, and level 0 is the signal itself.
+    ///
+    /// Only the coarsest averages are stored, so the finer ones are reconstructed from them
+    /// (which, like [`crate::WaveletTransform::invert`], costs `O(N)` per level). This returns
+    /// `None` if there's no such level.
+    pub fn approximation(&self, level: usize) -> Option<Vec<f64>> {
+        if level > self.levels() {
+            return None;
+        }
+        let mut approximation = self.approximation.clone();
+        self.reconstruct(&mut approximation, level);
+        Some(approximation)
+    }
+
+    /// The averages and differences at `level` (where level 1 is the finest), or `None` if there's
+    /// no such level.
+    pub fn level(&self, level: usize) -> Option<(Vec<f64>, &[f64])> {
+        let detail = self.detail(level)?;
+        Some((self.approximation(level)?, detail))
+    }
+
+    /// Reconstruct the averages at `level` from the coarsest averages (held in `approximation`).
+    fn reconstruct(&self, approximation: &mut Vec<f64>, level: usize) {
+        let bank = F::filter_bank();
+        let mut previous = vec![0.0; approximation.len()];
+        for j in (level..self.levels()).rev() {
+            level_inverse_swt(
+                &bank,
+                approximation,
+                &self.details[j],
+                1 << j,
+                &mut previous,
+            );
+            std::mem::swap(approximation, &mut previous);
+        }
+    }
+}
+
+impl<F: Filter> super::WaveletTransform for StationaryWavelet<F> {
+    fn transform<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect())
+    }
+
+    fn transform_in_place(data: Vec<f64>) -> Self {
+        let levels = Self::max_levels(data.len());
+        Self::transform_levels(data, levels)
+    }
+
+    fn invert(&self) -> Vec<f64> {
+        // approximation() only fails for a level beyond the transform
+        self.approximation(0).unwrap_or_default()
+    }
+
+    fn invert_in_place(&mut self) -> Vec<f64> {
+        let mut data = std::mem::take(&mut self.approximation);
+        self.reconstruct(&mut data, 0);
+        self.details.clear();
+        data
+    }
+}
+
+impl<F: Filter> DetailCoefficients for StationaryWavelet<F> {
+    fn detail_levels(&self) -> usize {
+        self.levels()
+    }
+
+    fn detail_coefficients(&self, level: usize) -> Option<&[f64]> {
+        self.detail(level)
+    }
+
+    fn detail_coefficients_mut(&mut self, level: usize) -> Option<&mut [f64]> {
+        self.details
+            .get_mut(level.checked_sub(1)?)
+            .map(|d| &mut d[..])
+    }
+
+    fn signal_len(&self) -> usize {
+        self.approximation.len()
+    }
+
+    fn noise_gain(&self, level: usize) -> f64 {
+        // the same filters as the decimated transform, just without the decimation
+        F::filter_bank().analysis_gain(level)
+    }
+}
+
+/// A single level of the stationary transform, with the filters upsampled by `step`.
+///
+/// `avg[n] = Σ ~h_m src[n + step*m]` and `diff[n] = Σ ~g_m src[n + step*m]`, periodically.
+fn level_swt(bank: &FilterBank, src: &[f64], step: usize, avg: &mut [f64], diff: &mut [f64]) {
+    let len = src.len();
+    let dual_scaling = bank.dual_scaling();
+    let dual_wavelet = bank.dual_wavelet();
+    for n in 0..len {
+        let (mut a, mut d) = (0.0, 0.0);
+        for m in 0..bank.len() {
+            let v = src[(n + (step * m) % len) % len];
+            a += dual_scaling[m] * v;
+            d += dual_wavelet[m] * v;
+        }
+        avg[n] = a;
+        diff[n] = d;
+    }
+}
+
+/// Invert a single level of the stationary transform, with the filters upsampled by `step`.
+///
+/// Both the even and odd samples (at this level's spacing) give a complete reconstruction, and
+/// `dest[n] = ½ Σ (h_m avg[n - step*m] + g_m diff[n - step*m])` is their average.
+fn level_inverse_swt(bank: &FilterBank, avg: &[f64], diff: &[f64], step: usize, dest: &mut [f64]) {
+    let len = avg.len();
+    let scaling = bank.scaling();
+    let wavelet = bank.wavelet();
+    for (n, value) in dest.iter_mut().enumerate() {
+        let mut sum = 0.0;
+        for m in 0..bank.len() {
+            let pos = (n + len - (step * m) % len) % len;
+            sum += scaling[m] * avg[pos] + wavelet[m] * diff[pos];
+        }
+        *value = sum / 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biorthogonal::Bior2_4;
+    use crate::boundary::BoundaryMode;
+    use crate::daubechies::Daub4;
+    use crate::test_util::{assert_close, DATA};
+    use crate::wavelet::FilterWavelet;
+    use crate::WaveletTransform;
+
+    #[test]
+    fn inverts() {
+        for len in [1, 2, 8, 13] {
+            let data = &DATA[..len];
+            let daub4 = StationaryWavelet::<Daub4>::transform(data);
+            assert_close(data, &daub4.invert(), "Daub4");
+
+            let mut bior = StationaryWavelet::<Bior2_4>::transform(data);
+            assert_eq!(StationaryWavelet::<Bior2_4>::max_levels(len), bior.levels());
+            assert_close(data, &bior.invert_in_place(), "Bior2_4");
+        }
+    }
+
+    #[test]
+    fn is_shift_invariant() {
+        let wavelet = StationaryWavelet::<Daub4>::try_transform_with(&DATA, 3).unwrap();
+        let mut shifted = DATA;
+        shifted.rotate_right(1);
+        let shifted = StationaryWavelet::<Daub4>::try_transform_with(&shifted, 3).unwrap();
+
+        for level in 1..=3 {
+            let mut expected = wavelet.detail(level).unwrap().to_vec();
+            expected.rotate_right(1);
+            assert_close(&expected, shifted.detail(level).unwrap(), "Shifted details");
+        }
+    }
+
+    #[test]
+    fn even_samples_are_the_decimated_transform() {
+        let data = &DATA[..8];
+        let stationary = StationaryWavelet::<Daub4>::try_transform_with(data, 1).unwrap();
+        let decimated =
+            FilterWavelet::<Daub4>::try_transform_with(data, BoundaryMode::Periodization, 1)
+                .unwrap();
+
+        let (approximation, detail) = stationary.level(1).unwrap();
+        let evens = |v: &[f64]| v.iter().step_by(2).copied().collect::<Vec<f64>>();
+        assert_close(
+            &decimated.coefficients()[..4],
+            &evens(&approximation),
+            "Averages",
+        );
+        assert_close(
+            &decimated.coefficients()[4..],
+            &evens(detail),
+            "Differences",
+        );
+        assert_close(data, &stationary.approximation(0).unwrap(), "Signal");
+        assert!(stationary.level(2).is_none());
+    }
+
+    #[test]
+    fn reports_invalid_levels() {
+        assert_eq!(
+            WaveletError::LevelTooDeep {
+                requested: 4,
+                max: 3
+            },
+            HaarStationaryWavelet::try_transform_with(&DATA, 4).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,897 @@
+// The Symlet family of near-symmetric orthogonal wavelets.
+use crate::filter::{Filter, FilterBank};
+use crate::wavelet::{OrthogonalDecomposition, OrthogonalWavelet};
+
+// Scaling coefficients for the Symlets. These solve the same equations as the Daubechies wavelets
+// (see `matrix.md`) and so have the same lengths and vanishing moments, but are built from a
+// different choice of roots when factoring the Daubechies polynomial: of all the possible choices,
+// we keep the one whose frequency response is closest to having linear phase (i.e. the "least
+// asymmetric" solution). For two vanishing moments there is no choice to make, so sym2 is DAUB4.
+// Every filter is the way round which puts most of its energy first, like the Daubechies filters.
+
+const SYM2: [f64; 4] = [
+    0.48296291314453416,
+    0.8365163037378079,
+    0.2241438680420134,
+    -0.12940952255126037,
+];
+
+const SYM3: [f64; 6] = [
+    0.33267055295008263,
+    0.8068915093110925,
+    0.45987750211849154,
+    -0.13501102001025458,
+    -0.08544127388202666,
+    0.03522629188570953,
+];
+
+const SYM4: [f64; 8] = [
+    -0.07576571478950221,
+    -0.029635527646002493,
+    0.497618667632775,
+    0.8037387518051321,
+    0.29785779560530606,
+    -0.09921954357663353,
+    -0.012603967262031304,
+    0.032223100604051466,
+];
+
+const SYM5: [f64; 10] = [
+    0.027333068344998768,
+    0.02951949092570626,
+    -0.039134249302313844,
+    0.19939753397685558,
+    0.7234076904040407,
+    0.633978963456792,
+    0.01660210576451085,
+    -0.17532808990805623,
+    -0.021101834024689042,
+    0.019538882735249827,
+];
+
+const SYM6: [f64; 12] = [
+    0.015404109327044824,
+    0.0034907120842221626,
+    -0.11799011114852002,
+    -0.04831174258569806,
+    0.49105594192797375,
+    0.787641141028651,
+    0.3379294217281658,
+    -0.07263752278637658,
+    -0.02106029251237085,
+    0.04472490177078139,
+    0.0017677118642540077,
+    -0.00780070832503238,
+];
+
+const SYM7: [f64; 14] = [
+    0.012015419283549189,
+    0.017213376300804502,
+    -0.06490800354718848,
+    -0.06413128980738582,
+    0.3602184609062602,
+    0.7819215932917282,
+    0.4836109156822677,
+    -0.05680447688966697,
+    -0.1010109208684203,
+    0.04474234946835238,
+    0.020464207577546033,
+    -0.01812660513133846,
+    -0.003283297847466811,
+    0.0022918339540537714,
+];
+
+const SYM8: [f64; 16] = [
+    0.007695482295128694,
+    0.010818523825735088,
+    -0.06723942736585652,
+    -0.13739042116486172,
+    0.16578751538520323,
+    0.6945630391089125,
+    0.6609508750869733,
+    0.10852960663226743,
+    -0.10992103611150919,
+    0.0409447988730862,
+    0.060992833647620405,
+    -0.015005524506655965,
+    -0.012327275876701465,
+    0.005477453351402692,
+    0.0011678141256890836,
+    -0.0008306949333386438,
+];
+
+const SYM9: [f64; 18] = [
+    0.005774604535965781,
+    0.013963636183296569,
+    -0.03484602374285066,
+    -0.11433430631248108,
+    0.08056700235853395,
+    0.5926551385706292,
+    0.7374707614342205,
+    0.2337782884637422,
+    -0.1432929768081533,
+    -0.021148031085692288,
+    0.08561240171755218,
+    -0.00021895156907497003,
+    -0.029536143419590332,
+    0.004067656322053093,
+    0.005984552518092256,
+    -0.001916107013297193,
+    -0.0006273974072228845,
+    0.0002594576273718927,
+];
+
+const SYM10: [f64; 20] = [
+    0.0008625782262259724,
+    0.0007154205420543397,
+    -0.007056764062587304,
+    0.0005956827837425191,
+    0.04968612664694288,
+    0.026240365058448987,
+    -0.12155210554854895,
+    -0.015019238839137859,
+    0.5137098733480263,
+    0.7669548365606096,
+    0.34021601302346216,
+    -0.08787871151197514,
+    -0.0670899078083818,
+    0.03384235466357522,
+    -0.0008687521096892581,
+    -0.02300546135349751,
+    -0.0011404297952173285,
+    0.005071649198531799,
+    0.00034014926631480987,
+    -0.0004101159158043983,
+];
+
+const SYM11: [f64; 22] = [
+    0.0006049755444670652,
+    0.0007643413858299387,
+    -0.005802940653980816,
+    -0.005988363304927519,
+    0.031753354999600504,
+    0.035263653806012166,
+    -0.0931755926688397,
+    -0.08916610271150285,
+    0.3577701168341381,
+    0.7665230477033109,
+    0.5010675488454238,
+    -0.017005487753640736,
+    -0.10574469945433015,
+    0.03595529973403713,
+    0.02814882477537615,
+    -0.026418638246881582,
+    -0.009140435782169434,
+    0.008709770719732784,
+    0.0018010896927056514,
+    -0.0016696173319946611,
+    -0.00017546094584372968,
+    0.00013887718657188376,
+];
+
+const SYM12: [f64; 24] = [
+    -0.00020526600487137938,
+    -0.00017690949629193344,
+    0.002104447335629671,
+    0.0006915974586788278,
+    -0.013053840998593582,
+    -0.001287033317152989,
+    0.06005859623424475,
+    0.031256859883591684,
+    -0.12359121292129573,
+    -0.007517992473075242,
+    0.5166743899411825,
+    0.7608721850415805,
+    0.34345150160951965,
+    -0.08927100096836146,
+    -0.08017578174217259,
+    0.030686743515091555,
+    0.0018619254598864197,
+    -0.025493025089340912,
+    -0.0005948327807239624,
+    0.00863423079172048,
+    0.0006610376737514791,
+    -0.001386550262370246,
+    -8.418262000974747e-05,
+    9.767610247723154e-05,
+];
+
+const SYM13: [f64; 26] = [
+    0.0003209255952060918,
+    0.000835499481191184,
+    -0.0028811075387286896,
+    -0.009995107053564633,
+    0.004660888062393009,
+    0.031676767749845826,
+    -0.024052980701454122,
+    -0.10141565866863525,
+    0.1308798589233565,
+    0.6261529425725961,
+    0.7078043569884411,
+    0.19734258417706066,
+    -0.1676972995843332,
+    -0.047640718231696355,
+    0.08298522327090556,
+    0.007215339947642913,
+    -0.03600865594689649,
+    0.004946543698878339,
+    0.014161228144431676,
+    -0.0027744645016502412,
+    -0.0035879818824467603,
+    0.0009189968796399323,
+    0.0005612930761101525,
+    -0.00017091265192767876,
+    -3.8967220437383125e-05,
+    1.4967787166743388e-05,
+];
+
+const SYM14: [f64; 28] = [
+    4.886462941519962e-05,
+    4.024306903743931e-05,
+    -0.0006169819105608121,
+    -0.0003402317924311618,
+    0.003928482068575019,
+    0.0006852905596424015,
+    -0.019351534256228764,
+    -0.0039053310774013724,
+    0.06709867559977023,
+    0.033339229883137034,
+    -0.12692108492556942,
+    -0.004216040297268032,
+    0.5167105352637036,
+    0.7565322361167204,
+    0.3483543508884237,
+    -0.08733774970681711,
+    -0.08843910772317169,
+    0.0292341274083238,
+    0.005838521955027694,
+    -0.02622180812101198,
+    -0.0001330835589800499,
+    0.011729309261388722,
+    0.0007970566239164852,
+    -0.002783535422955635,
+    -0.00022737470733015942,
+    0.00037467186621825617,
+    1.946123955648847e-05,
+    -2.3630560035158373e-05,
+];
+
+const SYM15: [f64; 30] = [
+    3.316410296794507e-05,
+    3.951494777871801e-05,
+    -0.0004526823191715911,
+    -0.0004676874740418021,
+    0.003179189806516048,
+    0.0031457650188374373,
+    -0.013929278699883266,
+    -0.012946966320645304,
+    0.04698647841805622,
+    0.04822587669972962,
+    -0.10717948552669601,
+    -0.10262675737984209,
+    0.35104002611310314,
+    0.7556493511052875,
+    0.5139166484623193,
+    0.008784234310659866,
+    -0.10822521010803804,
+    0.02619224785614083,
+    0.03166764240558633,
+    -0.028925625964237724,
+    -0.013311153300489521,
+    0.01366421884149359,
+    0.004228011452162069,
+    -0.004496076579879446,
+    -0.0009792281693813676,
+    0.0009952501856911554,
+    0.0001426594537141032,
+    -0.00013495761870850793,
+    -1.0000904217898589e-05,
+    8.393558283621892e-06,
+];
+
+const SYM16: [f64; 32] = [
+    2.4886130446379385e-05,
+    5.004404862357365e-05,
+    -0.00031806461542141837,
+    -0.0006587395993233103,
+    0.0019361521588140687,
+    0.0039049995940054006,
+    -0.008654614801834605,
+    -0.017345719766511832,
+    0.02596340814931507,
+    0.055710956634586896,
+    -0.05442474740446879,
+    -0.10932173814447667,
+    0.24200704402514653,
+    0.7113951510631492,
+    0.616775904530487,
+    0.08221197735161943,
+    -0.1556312111138001,
+    -0.011154273612198876,
+    0.05829728244128703,
+    -0.01635633360087952,
+    -0.027344240407559332,
+    0.012766758253025286,
+    0.011195417510109423,
+    -0.005449273998107065,
+    -0.003333238873398262,
+    0.0016544689065060832,
+    0.0006981233313770532,
+    -0.0003428905100385175,
+    -9.075574077493127e-05,
+    4.409773896356807e-05,
+    5.435866822392437e-06,
+    -2.7031723961574194e-06,
+];
+
+const SYM17: [f64; 34] = [
+    1.721702318080583e-05,
+    4.23102171710519e-05,
+    -0.0002252469120912276,
+    -0.000628507105836367,
+    0.0013159760159410045,
+    0.004424422952513999,
+    -0.003910113349960675,
+    -0.01736891746825239,
+    0.009161590884635964,
+    0.048349517047279286,
+    -0.028977977387259837,
+    -0.1294984389719971,
+    0.11181766943871693,
+    0.617178570242433,
+    0.7099871933288563,
+    0.226041752645245,
+    -0.14235707548948337,
+    -0.0517737932306164,
+    0.07718538141057193,
+    0.009501571122213247,
+    -0.040241272186155454,
+    0.00312811052850093,
+    0.018730174670250514,
+    -0.0034696845304714127,
+    -0.006961065641906812,
+    0.0015894953595571879,
+    0.0018769542944214084,
+    -0.0005069901932429293,
+    -0.00035164416221670363,
+    0.00011156365776838372,
+    4.1344725612858735e-05,
+    -1.5147376865057789e-05,
+    -2.325476566074657e-06,
+    9.462911471870543e-07,
+];
+
+const SYM18: [f64; 36] = [
+    -5.77302097411955e-06,
+    -1.0629377097786388e-05,
+    9.008396397461617e-05,
+    0.00017478285687384524,
+    -0.0006412660275794476,
+    -0.0012594976637293628,
+    0.003051716822042862,
+    0.0058707442520093585,
+    -0.011249441952450555,
+    -0.02218928491803132,
+    0.028270981624499623,
+    0.05926374361925724,
+    -0.0633695003518183,
+    -0.12527645854546857,
+    0.2297433337725436,
+    0.7048523607631126,
+    0.6241136213072419,
+    0.10226183142756706,
+    -0.14355140332421767,
+    -0.011008530304479172,
+    0.05980430369432843,
+    -0.014259655331260092,
+    -0.028599633930839776,
+    0.013334513325393127,
+    0.012948619013008353,
+    -0.006433011727522408,
+    -0.004466794426353487,
+    0.0022842700813490496,
+    0.0011562536744791046,
+    -0.0005947266367158051,
+    -0.00021097175935084786,
+    0.00010805757812150247,
+    2.391294638351989e-05,
+    -1.2412998618368376e-05,
+    -1.2608383702474024e-06,
+    6.847857865470607e-07,
+];
+
+const SYM19: [f64; 38] = [
+    1.9456220580224237e-06,
+    2.04152729769768e-06,
+    -3.752619774035637e-05,
+    -4.9873724498685154e-05,
+    0.00029375210671291285,
+    0.0004028909774523232,
+    -0.0014032251079928223,
+    -0.0014590161091627256,
+    0.006358025138037752,
+    0.005200482621719818,
+    -0.023838056235186734,
+    -0.025917812161499246,
+    0.04433404542341444,
+    0.04571417477111841,
+    -0.10682715267500395,
+    -0.07798899398784172,
+    0.37935531421253565,
+    0.7535819473450875,
+    0.49537200703456646,
+    -0.00937874838459332,
+    -0.12688295659390186,
+    0.0235342137498116,
+    0.04749367971679956,
+    -0.019018399493396617,
+    -0.011668362493638807,
+    0.018382864335116753,
+    0.00639247125364494,
+    -0.007921738538443893,
+    -0.0024058449462858395,
+    0.002494976745421074,
+    0.0006733459647868663,
+    -0.0005530591840920159,
+    -0.00011653128332302106,
+    8.95847648294252e-05,
+    1.236845212970402e-05,
+    -9.24792902034771e-06,
+    -5.182050653453317e-07,
+    4.938612414596931e-07,
+];
+
+const SYM20: [f64; 40] = [
+    1.3851899854493926e-06,
+    2.56874593978856e-06,
+    -2.3989439858018047e-05,
+    -4.4913493500076025e-05,
+    0.00020205725067381163,
+    0.00038775694709199435,
+    -0.0010567081071532587,
+    -0.0020081348463221716,
+    0.004315365293430331,
+    0.008155850463329757,
+    -0.013135967590303388,
+    -0.025583440024953993,
+    0.032012682469243714,
+    0.06555006872289021,
+    -0.06451576664334994,
+    -0.12931488606514216,
+    0.22762800158820257,
+    0.7017042289078668,
+    0.6254442770017576,
+    0.1100093543879213,
+    -0.14192242000110036,
+    -0.016398623329059725,
+    0.05827840091000209,
+    -0.013582580706789903,
+    -0.030227705448421273,
+    0.013399364866696833,
+    0.014380388810313676,
+    -0.007364905266412118,
+    -0.00564049490568102,
+    0.002917819584541607,
+    0.0017010019786609686,
+    -0.0008978793050406898,
+    -0.0003909540158470716,
+    0.00020507590449478716,
+    6.333811240497165e-05,
+    -3.321174923209228e-05,
+    -6.424395944624323e-06,
+    3.4362965637348256e-06,
+    3.131295312743972e-07,
+    -1.6885433633248292e-07,
+];
+
+/// The Symlet with 2 vanishing moments, which has 4 coefficients.
+#[derive(Debug)]
+pub struct Sym2;
+
+impl Filter for Sym2 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM2)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym2 wavelet.
+pub type Sym2Wavelet = OrthogonalWavelet<Sym2>;
+
+/// The Multi-Resolution Decomposition using the sym2 wavelet.
+pub type Sym2Decomposition = OrthogonalDecomposition<Sym2>;
+
+/// The Symlet with 3 vanishing moments, which has 6 coefficients.
+#[derive(Debug)]
+pub struct Sym3;
+
+impl Filter for Sym3 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM3)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym3 wavelet.
+pub type Sym3Wavelet = OrthogonalWavelet<Sym3>;
+
+/// The Multi-Resolution Decomposition using the sym3 wavelet.
+pub type Sym3Decomposition = OrthogonalDecomposition<Sym3>;
+
+/// The Symlet with 4 vanishing moments, which has 8 coefficients.
+#[derive(Debug)]
+pub struct Sym4;
+
+impl Filter for Sym4 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM4)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym4 wavelet.
+pub type Sym4Wavelet = OrthogonalWavelet<Sym4>;
+
+/// The Multi-Resolution Decomposition using the sym4 wavelet.
+pub type Sym4Decomposition = OrthogonalDecomposition<Sym4>;
+
+/// The Symlet with 5 vanishing moments, which has 10 coefficients.
+#[derive(Debug)]
+pub struct Sym5;
+
+impl Filter for Sym5 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM5)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym5 wavelet.
+pub type Sym5Wavelet = OrthogonalWavelet<Sym5>;
+
+/// The Multi-Resolution Decomposition using the sym5 wavelet.
+pub type Sym5Decomposition = OrthogonalDecomposition<Sym5>;
+
+/// The Symlet with 6 vanishing moments, which has 12 coefficients.
+#[derive(Debug)]
+pub struct Sym6;
+
+impl Filter for Sym6 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM6)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym6 wavelet.
+pub type Sym6Wavelet = OrthogonalWavelet<Sym6>;
+
+/// The Multi-Resolution Decomposition using the sym6 wavelet.
+pub type Sym6Decomposition = OrthogonalDecomposition<Sym6>;
+
+/// The Symlet with 7 vanishing moments, which has 14 coefficients.
+#[derive(Debug)]
+pub struct Sym7;
+
+impl Filter for Sym7 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM7)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym7 wavelet.
+pub type Sym7Wavelet = OrthogonalWavelet<Sym7>;
+
+/// The Multi-Resolution Decomposition using the sym7 wavelet.
+pub type Sym7Decomposition = OrthogonalDecomposition<Sym7>;
+
+/// The Symlet with 8 vanishing moments, which has 16 coefficients.
+#[derive(Debug)]
+pub struct Sym8;
+
+impl Filter for Sym8 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM8)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym8 wavelet.
+pub type Sym8Wavelet = OrthogonalWavelet<Sym8>;
+
+/// The Multi-Resolution Decomposition using the sym8 wavelet.
+pub type Sym8Decomposition = OrthogonalDecomposition<Sym8>;
+
+/// The Symlet with 9 vanishing moments, which has 18 coefficients.
+#[derive(Debug)]
+pub struct Sym9;
+
+impl Filter for Sym9 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM9)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym9 wavelet.
+pub type Sym9Wavelet = OrthogonalWavelet<Sym9>;
+
+/// The Multi-Resolution Decomposition using the sym9 wavelet.
+pub type Sym9Decomposition = OrthogonalDecomposition<Sym9>;
+
+/// The Symlet with 10 vanishing moments, which has 20 coefficients.
+#[derive(Debug)]
+pub struct Sym10;
+
+impl Filter for Sym10 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM10)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym10 wavelet.
+pub type Sym10Wavelet = OrthogonalWavelet<Sym10>;
+
+/// The Multi-Resolution Decomposition using the sym10 wavelet.
+pub type Sym10Decomposition = OrthogonalDecomposition<Sym10>;
+
+/// The Symlet with 11 vanishing moments, which has 22 coefficients.
+#[derive(Debug)]
+pub struct Sym11;
+
+impl Filter for Sym11 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM11)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym11 wavelet.
+pub type Sym11Wavelet = OrthogonalWavelet<Sym11>;
+
+/// The Multi-Resolution Decomposition using the sym11 wavelet.
+pub type Sym11Decomposition = OrthogonalDecomposition<Sym11>;
+
+/// The Symlet with 12 vanishing moments, which has 24 coefficients.
+#[derive(Debug)]
+pub struct Sym12;
+
+impl Filter for Sym12 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM12)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym12 wavelet.
+pub type Sym12Wavelet = OrthogonalWavelet<Sym12>;
+
+/// The Multi-Resolution Decomposition using the sym12 wavelet.
+pub type Sym12Decomposition = OrthogonalDecomposition<Sym12>;
+
+/// The Symlet with 13 vanishing moments, which has 26 coefficients.
+#[derive(Debug)]
+pub struct Sym13;
+
+impl Filter for Sym13 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM13)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym13 wavelet.
+pub type Sym13Wavelet = OrthogonalWavelet<Sym13>;
+
+/// The Multi-Resolution Decomposition using the sym13 wavelet.
+pub type Sym13Decomposition = OrthogonalDecomposition<Sym13>;
+
+/// The Symlet with 14 vanishing moments, which has 28 coefficients.
+#[derive(Debug)]
+pub struct Sym14;
+
+impl Filter for Sym14 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM14)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym14 wavelet.
+pub type Sym14Wavelet = OrthogonalWavelet<Sym14>;
+
+/// The Multi-Resolution Decomposition using the sym14 wavelet.
+pub type Sym14Decomposition = OrthogonalDecomposition<Sym14>;
+
+/// The Symlet with 15 vanishing moments, which has 30 coefficients.
+#[derive(Debug)]
+pub struct Sym15;
+
+impl Filter for Sym15 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM15)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym15 wavelet.
+pub type Sym15Wavelet = OrthogonalWavelet<Sym15>;
+
+/// The Multi-Resolution Decomposition using the sym15 wavelet.
+pub type Sym15Decomposition = OrthogonalDecomposition<Sym15>;
+
+/// The Symlet with 16 vanishing moments, which has 32 coefficients.
+#[derive(Debug)]
+pub struct Sym16;
+
+impl Filter for Sym16 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM16)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym16 wavelet.
+pub type Sym16Wavelet = OrthogonalWavelet<Sym16>;
+
+/// The Multi-Resolution Decomposition using the sym16 wavelet.
+pub type Sym16Decomposition = OrthogonalDecomposition<Sym16>;
+
+/// The Symlet with 17 vanishing moments, which has 34 coefficients.
+#[derive(Debug)]
+pub struct Sym17;
+
+impl Filter for Sym17 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM17)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym17 wavelet.
+pub type Sym17Wavelet = OrthogonalWavelet<Sym17>;
+
+/// The Multi-Resolution Decomposition using the sym17 wavelet.
+pub type Sym17Decomposition = OrthogonalDecomposition<Sym17>;
+
+/// The Symlet with 18 vanishing moments, which has 36 coefficients.
+#[derive(Debug)]
+pub struct Sym18;
+
+impl Filter for Sym18 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM18)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym18 wavelet.
+pub type Sym18Wavelet = OrthogonalWavelet<Sym18>;
+
+/// The Multi-Resolution Decomposition using the sym18 wavelet.
+pub type Sym18Decomposition = OrthogonalDecomposition<Sym18>;
+
+/// The Symlet with 19 vanishing moments, which has 38 coefficients.
+#[derive(Debug)]
+pub struct Sym19;
+
+impl Filter for Sym19 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM19)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym19 wavelet.
+pub type Sym19Wavelet = OrthogonalWavelet<Sym19>;
+
+/// The Multi-Resolution Decomposition using the sym19 wavelet.
+pub type Sym19Decomposition = OrthogonalDecomposition<Sym19>;
+
+/// The Symlet with 20 vanishing moments, which has 40 coefficients.
+#[derive(Debug)]
+pub struct Sym20;
+
+impl Filter for Sym20 {
+    fn filter_bank() -> FilterBank {
+        FilterBank::orthogonal(&SYM20)
+    }
+}
+
+/// The Discrete Wavelet Transform using the sym20 wavelet.
+pub type Sym20Wavelet = OrthogonalWavelet<Sym20>;
+
+/// The Multi-Resolution Decomposition using the sym20 wavelet.
+pub type Sym20Decomposition = OrthogonalDecomposition<Sym20>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MRDecomposition, WaveletTransform};
+
+    fn banks() -> Vec<FilterBank> {
+        vec![
+            Sym2::filter_bank(),
+            Sym3::filter_bank(),
+            Sym4::filter_bank(),
+            Sym5::filter_bank(),
+            Sym6::filter_bank(),
+            Sym7::filter_bank(),
+            Sym8::filter_bank(),
+            Sym9::filter_bank(),
+            Sym10::filter_bank(),
+            Sym11::filter_bank(),
+            Sym12::filter_bank(),
+            Sym13::filter_bank(),
+            Sym14::filter_bank(),
+            Sym15::filter_bank(),
+            Sym16::filter_bank(),
+            Sym17::filter_bank(),
+            Sym18::filter_bank(),
+            Sym19::filter_bank(),
+            Sym20::filter_bank(),
+        ]
+    }
+
+    #[test]
+    fn coefficients_are_orthonormal() {
+        for bank in banks() {
+            assert!(
+                bank.is_admissible(1e-14),
+                "sym{} is not admissible!",
+                bank.len() / 2
+            );
+            assert!(
+                bank.is_orthonormal(1e-14),
+                "sym{} is not orthonormal!",
+                bank.len() / 2
+            );
+        }
+    }
+
+    #[test]
+    fn wavelets_have_vanishing_moments() {
+        for bank in banks() {
+            assert!(
+                bank.vanishing_moments(1e-10) >= bank.len() / 2,
+                "sym{} only has {} vanishing moments!",
+                bank.len() / 2,
+                bank.vanishing_moments(1e-10)
+            );
+        }
+    }
+
+    #[test]
+    fn tables_have_one_orientation() {
+        for bank in banks() {
+            let h = bank.scaling();
+            let centroid: f64 = h.iter().enumerate().map(|(n, c)| n as f64 * c * c).sum();
+            assert!(
+                centroid <= (h.len() - 1) as f64 / 2.0,
+                "sym{} has most of its energy last!",
+                bank.len() / 2
+            );
+        }
+    }
+
+    #[test]
+    fn sym2_is_daub4() {
+        use crate::daubechies::Daub4;
+
+        assert_eq!(
+            Daub4::filter_bank(),
+            Sym2::filter_bank(),
+            "sym2 should be the same as DAUB4"
+        );
+    }
+
+    #[test]
+    fn transforms_invert() {
+        let data: Vec<f64> = (0..64).map(|v| ((v * 37) % 23) as f64 - 11.0).collect();
+
+        let wavelet = Sym8Wavelet::transform(&data);
+        let decomposed: Sym8Wavelet = Sym8Decomposition::decompose(&data).into();
+        assert_eq!(
+            wavelet.coefficients(),
+            decomposed.coefficients(),
+            "Decomposition does not match the transform!"
+        );
+
+        let mut in_place = Sym8Wavelet::transform_in_place(data.clone());
+        let inverse = in_place.invert_in_place();
+        let delta = 1e-10;
+        for (pos, expected) in data.iter().enumerate() {
+            assert!(
+                (expected - inverse[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                inverse[pos]
+            );
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Approximate range queries from a compressed Haar transform of cumulative data.
+//!
+//! This is the range query of `notes/operations.md`: if the data is turned into a cumulative
+//! distribution first, then the total over any range is the difference of two point queries,
+//! each of which takes `O(log N)` time on the Haar transform. Keeping only some of the
+//! coefficients makes a synopsis which is much smaller than the data, at the cost of some error in
+//! the answers, which is what a query planner needs to estimate the selectivity of a predicate.
+//!
+//! The error of an answer can be bounded without knowing the coefficients that were dropped: at
+//! each level, only the coefficients covering the two ends of the range contribute, and then only
+//! if the ends are in different halves of their supports. So keeping the largest dropped
+//! magnitude at each level is enough for a bound, which is tightened further by the L2 error of
+//! the whole cumulative distribution.
+use std::ops::Range;
+
+use crate::boundary::BoundaryMode;
+use crate::compression::Budget;
+use crate::error::{self, WaveletError};
+use crate::filter::Filter;
+use crate::haar::{Haar, HaarWavelet};
+use crate::sparse::{self, SparseWavelet};
+use crate::wavelet;
+use crate::WaveletTransform;
+
+/// An approximate answer to a query, with a bound on its error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// The estimated answer.
+    pub value: f64,
+    /// The most that the estimate can differ from the true answer (apart from rounding).
+    pub error_bound: f64,
+}
+
+/// A synopsis of the frequencies of values in `0..N`, answering range queries approximately.
+#[derive(Debug, Clone)]
+pub struct RangeSynopsis {
+    counts: CumulativeTransform,
+    sums: CumulativeTransform,
+    domain: usize,
+    /// The exact number of values, so that selectivities are only as wrong as their counts.
+    total: f64,
+}
+
+impl RangeSynopsis {
+    /// Build a synopsis of the raw values in the domain `0..domain`, each of which is counted
+    /// once, or return an error if any of the values is outside the domain.
+    ///
+    /// If there's a budget, it's shared between the two transforms in the synopsis (one for
+    /// counts, one for sums), otherwise all of the non-zero coefficients are kept and the answers
+    /// are exact.
+    pub fn try_from_values(
+        values: &[usize],
+        domain: usize,
+        budget: Option<Budget>,
+    ) -> Result<RangeSynopsis, WaveletError> {
+        if let Some(index) = values.iter().position(|&v| v >= domain) {
+            return Err(WaveletError::OutOfDomain { index, domain });
+        }
+        let mut frequencies = vec![0.0; domain];
+        for &v in values {
+            frequencies[v] += 1.0;
+        }
+        Ok(Self::build(&frequencies, budget))
+    }
+
+    /// Build a synopsis from the frequency of each value in the domain, as in
+    /// [`RangeSynopsis::try_from_values`], or return an error if any of the frequencies isn't
+    /// finite.
+    pub fn try_from_frequencies<T>(
+        frequencies: &[T],
+        budget: Option<Budget>,
+    ) -> Result<RangeSynopsis, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(frequencies)?;
+        let frequencies: Vec<f64> = frequencies.iter().map(|f| (*f).into()).collect();
+        Ok(Self::build(&frequencies, budget))
+    }
+
+    fn build(frequencies: &[f64], budget: Option<Budget>) -> RangeSynopsis {
+        let weighted: Vec<f64> = frequencies
+            .iter()
+            .enumerate()
+            .map(|(v, f)| v as f64 * f)
+            .collect();
+        let (counts, sums) = match budget {
+            Some(budget) => {
+                let (counts, sums) = split(budget);
+                (Some(counts), Some(sums))
+            }
+            None => (None, None),
+        };
+        RangeSynopsis {
+            counts: CumulativeTransform::new(frequencies, counts),
+            sums: CumulativeTransform::new(&weighted, sums),
+            domain: frequencies.len(),
+            total: frequencies.iter().sum(),
+        }
+    }
+
+    /// The number of values in the domain, `N`.
+    pub fn domain(&self) -> usize {
+        self.domain
+    }
+
+    /// The number of coefficients kept in the synopsis.
+    pub fn size(&self) -> usize {
+        self.counts.transform.coefficients().len() + self.sums.transform.coefficients().len()
+    }
+
+    /// The number of values in `range` (the parts of it outside the domain are empty).
+    pub fn count(&self, range: Range<usize>) -> Estimate {
+        self.counts.query(self.clamp(range))
+    }
+
+    /// The sum of the values in `range`.
+    pub fn sum(&self, range: Range<usize>) -> Estimate {
+        self.sums.query(self.clamp(range))
+    }
+
+    /// The fraction of all the values which are in `range`.
+    pub fn selectivity(&self, range: Range<usize>) -> Estimate {
+        if self.total <= 0.0 {
+            return Estimate {
+                value: 0.0,
+                error_bound: 0.0,
+            };
+        }
+        let count = self.count(range);
+        Estimate {
+            value: (count.value / self.total).clamp(0.0, 1.0),
+            error_bound: count.error_bound / self.total,
+        }
+    }
+
+    fn clamp(&self, range: Range<usize>) -> Range<usize> {
+        let end = range.end.min(self.domain);
+        range.start.min(end)..end
+    }
+}
+
+/// Share a budget for the whole synopsis between its counts and its sums.
+///
+/// The counts get the extra coefficient of an odd [`Budget::Largest`], an error budget is split
+/// so that the two L2 errors together are within it, and since each transform holds the fraction
+/// of its own energy, the synopsis holds that fraction of the total.
+fn split(budget: Budget) -> (Budget, Budget) {
+    match budget {
+        Budget::Largest(keep) => (Budget::Largest(keep.div_ceil(2)), Budget::Largest(keep / 2)),
+        Budget::Error(error) => {
+            let error = error * std::f64::consts::FRAC_1_SQRT_2;
+            (Budget::Error(error), Budget::Error(error))
+        }
+        Budget::Energy(fraction) => (Budget::Energy(fraction), Budget::Energy(fraction)),
+    }
+}
+
+/// The sparse Haar transform of the cumulative sums `C[k] = Σ_{i<k} x[i]` for `k` in `0..=N`,
+/// with what's needed to bound the error of its queries.
+#[derive(Debug, Clone)]
+struct CumulativeTransform {
+    transform: SparseWavelet,
+    /// The largest magnitude of the dropped coefficients at each level, with the final averages
+    /// last.
+    dropped: Vec<f64>,
+    /// The L2 error of the whole cumulative distribution.
+    error: f64,
+}
+
+impl CumulativeTransform {
+    fn new(data: &[f64], budget: Option<Budget>) -> CumulativeTransform {
+        let mut cumulative = Vec::with_capacity(data.len() + 1);
+        let mut sum = 0.0;
+        cumulative.push(sum);
+        for x in data {
+            sum += x;
+            cumulative.push(sum);
+        }
+
+        let haar = HaarWavelet::transform(&cumulative);
+        let levels = haar.levels();
+        let Some(budget) = budget else {
+            return CumulativeTransform {
+                transform: SparseWavelet::try_from(&haar)
+                    .expect("The transform uses periodization"),
+                dropped: vec![0.0; levels + 1],
+                error: 0.0,
+            };
+        };
+
+        let compressed = haar.compress(budget);
+        let lengths = wavelet::level_lengths(
+            &Haar::filter_bank(),
+            cumulative.len(),
+            BoundaryMode::Periodization,
+            levels,
+        );
+        let mut kept = compressed.coefficients().iter().map(|c| c.0).peekable();
+        let mut dropped = vec![0.0f64; levels + 1];
+        for (pos, c) in haar.coefficients().iter().enumerate() {
+            if kept.next_if_eq(&pos).is_some() {
+                continue;
+            }
+            let (level, _, average) = sparse::locate(&lengths, pos);
+            let slot = if average { levels } else { level - 1 };
+            dropped[slot] = dropped[slot].max(c.abs());
+        }
+        CumulativeTransform {
+            transform: SparseWavelet::try_from(&compressed)
+                .expect("The transform uses periodization"),
+            dropped,
+            error: compressed.error(),
+        }
+    }
+
+    fn query(&self, range: Range<usize>) -> Estimate {
+        if range.is_empty() {
+            return Estimate {
+                value: 0.0,
+                error_bound: 0.0,
+            };
+        }
+        // the point queries can't fail, since both ends are within 0..=N
+        let value_at = |k| self.transform.value_at(k).unwrap_or_default();
+        let value = value_at(range.end) - value_at(range.start);
+
+        // at each level, the dropped coefficients only matter if the ends are in different halves
+        let (lo, hi) = (range.start, range.end);
+        let levels = self.dropped.len() - 1;
+        let mut bound: f64 = (1..=levels)
+            .filter(|level| lo >> (level - 1) != hi >> (level - 1))
+            .map(|level| {
+                2.0 * self.dropped[level - 1] * std::f64::consts::FRAC_1_SQRT_2.powi(level as i32)
+            })
+            .sum();
+        if lo >> levels != hi >> levels {
+            bound +=
+                2.0 * self.dropped[levels] * std::f64::consts::FRAC_1_SQRT_2.powi(levels as i32);
+        }
+        // and both ends together can't be wrong by more than the total error
+        bound = bound.min(std::f64::consts::SQRT_2 * self.error);
+
+        Estimate {
+            value,
+            error_bound: bound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frequencies which aren't very smooth, with a few gaps.
+    fn frequencies(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|v| {
+                if v % 7 == 3 {
+                    0.0
+                } else {
+                    ((v * v * 13) % 23) as f64
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn answers_exactly_without_a_budget() {
+        let frequencies = frequencies(37);
+        let synopsis = RangeSynopsis::try_from_frequencies(&frequencies, None).unwrap();
+        for lo in 0..=37 {
+            for hi in lo..=37 {
+                let count: f64 = frequencies[lo..hi].iter().sum();
+                let sum: f64 = (lo..hi).map(|v| v as f64 * frequencies[v]).sum();
+                let estimate = synopsis.count(lo..hi);
+                assert!(
+                    (estimate.value - count).abs() < 1e-9,
+                    "count({}..{})",
+                    lo,
+                    hi
+                );
+                assert_eq!(0.0, estimate.error_bound);
+                assert!(
+                    (synopsis.sum(lo..hi).value - sum).abs() < 1e-9,
+                    "sum({}..{})",
+                    lo,
+                    hi
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_errors_of_truncated_synopses() {
+        let frequencies = frequencies(37);
+        for budget in [
+            Budget::Largest(4),
+            Budget::Largest(12),
+            Budget::Energy(0.999),
+        ] {
+            let synopsis = RangeSynopsis::try_from_frequencies(&frequencies, Some(budget)).unwrap();
+            for lo in 0..=37 {
+                for hi in lo..=37 {
+                    let count: f64 = frequencies[lo..hi].iter().sum();
+                    let sum: f64 = (lo..hi).map(|v| v as f64 * frequencies[v]).sum();
+                    let estimate = synopsis.count(lo..hi);
+                    assert!(
+                        (estimate.value - count).abs() <= estimate.error_bound + 1e-9,
+                        "{:?}: count({}..{}) = {} but estimated {} ± {}",
+                        budget,
+                        lo,
+                        hi,
+                        count,
+                        estimate.value,
+                        estimate.error_bound
+                    );
+                    let estimate = synopsis.sum(lo..hi);
+                    assert!((estimate.value - sum).abs() <= estimate.error_bound + 1e-9);
+                }
+            }
+        }
+
+        let synopsis =
+            RangeSynopsis::try_from_frequencies(&frequencies, Some(Budget::Error(2.0))).unwrap();
+        assert!(synopsis.count(3..30).error_bound <= 2.0 * std::f64::consts::SQRT_2);
+        assert!(synopsis.counts.error.hypot(synopsis.sums.error) <= 2.0);
+        assert!(synopsis.size() < 2 * 38);
+    }
+
+    #[test]
+    fn shares_budget_between_transforms() {
+        let frequencies = frequencies(37);
+        for keep in [0, 1, 4, 7, 12] {
+            let synopsis =
+                RangeSynopsis::try_from_frequencies(&frequencies, Some(Budget::Largest(keep)))
+                    .unwrap();
+            assert_eq!(keep, synopsis.size());
+        }
+        for error in [1.0, 10.0, 100.0] {
+            let synopsis =
+                RangeSynopsis::try_from_frequencies(&frequencies, Some(Budget::Error(error)))
+                    .unwrap();
+            assert!(synopsis.counts.error.hypot(synopsis.sums.error) <= error);
+        }
+    }
+
+    #[test]
+    fn bounds_errors_of_selectivity() {
+        let frequencies = frequencies(37);
+        let total: f64 = frequencies.iter().sum();
+        for budget in [
+            Budget::Largest(4),
+            Budget::Largest(12),
+            Budget::Energy(0.999),
+        ] {
+            let synopsis = RangeSynopsis::try_from_frequencies(&frequencies, Some(budget)).unwrap();
+            for lo in 0..=37 {
+                for hi in lo..=37 {
+                    let selectivity = frequencies[lo..hi].iter().sum::<f64>() / total;
+                    let estimate = synopsis.selectivity(lo..hi);
+                    assert!(
+                        (estimate.value - selectivity).abs() <= estimate.error_bound + 1e-12,
+                        "{:?}: selectivity({}..{}) = {} but estimated {} ± {}",
+                        budget,
+                        lo,
+                        hi,
+                        selectivity,
+                        estimate.value,
+                        estimate.error_bound
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn estimates_selectivity_of_values() {
+        let values = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let synopsis = RangeSynopsis::try_from_values(&values, 10, None).unwrap();
+        assert_eq!(10, synopsis.domain());
+        assert!((synopsis.count(1..4).value - 5.0).abs() < 1e-9);
+        assert!((synopsis.sum(5..100).value - 30.0).abs() < 1e-9);
+        assert!((synopsis.selectivity(5..6).value - 3.0 / 11.0).abs() < 1e-9);
+        assert_eq!(0.0, synopsis.count(20..30).value);
+        assert_eq!(0.0, synopsis.count(4..4).value);
+
+        assert_eq!(
+            Err(WaveletError::OutOfDomain {
+                index: 5,
+                domain: 9
+            }),
+            RangeSynopsis::try_from_values(&values, 9, None).map(|s| s.domain())
+        );
+        assert_eq!(
+            Err(WaveletError::NonFiniteInput { index: 1 }),
+            RangeSynopsis::try_from_frequencies(&[1.0, f64::NAN], None).map(|s| s.domain())
+        );
+    }
+}
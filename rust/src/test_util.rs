@@ -0,0 +1,26 @@
+//! Helpers shared by the tests of the transforms.
+
+/// Data of an odd length, which isn't a power of two, so the boundaries of every level matter.
+pub const DATA: [f64; 13] = [
+    3.0, -1.0, 4.0, 1.0, -5.0, 9.0, 2.0, 6.0, -5.0, 3.0, 5.0, 8.0, -9.0,
+];
+
+/// Assert that two slices have the same length, and agree element by element up to rounding.
+pub fn assert_close(expected: &[f64], actual: &[f64], message: &str) {
+    assert_eq!(
+        expected.len(),
+        actual.len(),
+        "{}: incorrect length",
+        message
+    );
+    for (pos, (e, a)) in expected.iter().zip(actual).enumerate() {
+        assert!(
+            (e - a).abs() < 1e-12,
+            "{}: element at pos {} incorrect. Expected {} but was {}",
+            message,
+            pos,
+            e,
+            a
+        );
+    }
+}
@@ -0,0 +1,818 @@
+//! The two-dimensional Discrete Wavelet Transform, for images and other gridded data.
+//!
+//! The 2-D transform is separable: a level transforms every row of the data with the 1-D
+//! transform, and then every column of the result. Along each direction, the low-pass (average)
+//! half ends up first and the high-pass (difference) half second, so the data is divided into
+//! four quadrants, or _subbands_:
+//!
+//! ```text
+//! +----+----+
+//! | LL | HL |
+//! +----+----+
+//! | LH | HH |
+//! +----+----+
+//! ```
+//!
+//! where the first letter is the filter applied along the rows (horizontally), and the second the
+//! filter applied along the columns (vertically). So `HL` holds the horizontal differences, which
+//! pick out vertical edges, `LH` picks out horizontal edges, and `HH` diagonal detail. The next
+//! level then transforms the `LL` subband in the same way, and so on down to the coarsest level.
+//!
+//! That's the _non-standard_ (or pyramid) decomposition. The _standard_ decomposition instead
+//! performs the complete 1-D transform on every row, and then the complete 1-D transform on every
+//! column. That mixes scales--it has basis functions which are coarse horizontally but fine
+//! vertically--so rather than a pyramid of subbands, the result is a grid of blocks, one for each
+//! pair of a horizontal and a vertical band of the 1-D transform. See [`Decomposition`].
+use std::marker::PhantomData;
+
+use crate::boundary::BoundaryMode;
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::haar::Haar;
+use crate::wavelet;
+
+/// One of the four subbands of a level of the 2-D transform.
+///
+/// The first letter is the filter applied along the rows, and the second the filter applied along
+/// the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Averages in both directions.
+    LL,
+    /// Horizontal averages of vertical differences, which pick out horizontal edges.
+    LH,
+    /// Horizontal differences of vertical averages, which pick out vertical edges.
+    HL,
+    /// Differences in both directions, which pick out diagonal detail.
+    HH,
+}
+
+/// How the levels of the 2-D transform are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Decomposition {
+    /// Transform every row completely, and then every column completely.
+    ///
+    /// The basis functions are products of 1-D basis functions at any two scales, and the
+    /// coefficients are accessed with [`FilterWavelet2D::block`].
+    Standard,
+    /// Alternate a single level along the rows with a single level along the columns, and then
+    /// decompose the averages of both (the `LL` subband) further. This is the Mallat pyramid.
+    ///
+    /// The basis functions are products of 1-D basis functions at the same scale, and the
+    /// coefficients are accessed with [`FilterWavelet2D::subband`].
+    #[default]
+    NonStandard,
+}
+
+/// One band of the 1-D transform along a single direction, used to find a block of the standard
+/// decomposition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    /// The averages at the coarsest level.
+    Average,
+    /// The differences at the given level, where level 1 is the finest.
+    Detail(usize),
+}
+
+/// The two-dimensional Discrete Wavelet Transform of a wavelet with compact support.
+///
+/// The data is held in row-major order, `width` samples to a row. Each level transforms the data
+/// with [`BoundaryMode::Periodization`], so as in 1-D, a level with an odd number of samples along
+/// either axis has the last one repeated, and the coefficients are laid out along each axis like
+/// the 1-D transform, `[avg | d^J | ... | d^1]`. Those axes can be longer than the data, and where a
+/// subband is narrower (or shorter) than the space the 1-D layout gives it, the rest is zero.
+#[derive(Debug)]
+pub struct FilterWavelet2D<F: Filter> {
+    transform: Vec<f64>,
+    width: usize,
+    height: usize,
+    levels: usize,
+    decomposition: Decomposition,
+    filter: PhantomData<F>,
+}
+
+/// The two-dimensional Haar transform.
+pub type HaarWavelet2D = FilterWavelet2D<Haar>;
+
+impl<F: Filter> FilterWavelet2D<F> {
+    /// Perform as many levels of the 2-D transform as the dimensions allow.
+    ///
+    /// This panics if the data doesn't hold `width * height` samples.
+    pub fn transform<T>(data: &[T], width: usize, height: usize) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_in_place(data.iter().map(|v| (*v).into()).collect(), width, height)
+    }
+
+    /// Perform as many levels of the 2-D transform as the dimensions allow, in place.
+    ///
+    /// This panics if the data doesn't hold `width * height` samples.
+    pub fn transform_in_place(data: Vec<f64>, width: usize, height: usize) -> Self {
+        let levels = Self::max_levels(width, height);
+        Self::try_transform_in_place(data, width, height, levels)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Perform as many levels of the 2-D transform as the dimensions allow, using the given
+    /// decomposition.
+    ///
+    /// This panics if the data doesn't hold `width * height` samples.
+    pub fn transform_with<T>(
+        data: &[T],
+        width: usize,
+        height: usize,
+        decomposition: Decomposition,
+    ) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let levels = Self::max_levels(width, height);
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Self::try_transform_in_place_with(floats, width, height, levels, decomposition)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Perform `levels` levels of the 2-D transform, or return an error if the data doesn't hold
+    /// `width * height` finite samples, or the dimensions can't support that many levels.
+    pub fn try_transform<T>(
+        data: &[T],
+        width: usize,
+        height: usize,
+        levels: usize,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Self::try_transform_in_place(floats, width, height, levels)
+    }
+
+    /// Perform `levels` levels of the 2-D transform using the given decomposition, or return an
+    /// error as in [`FilterWavelet2D::try_transform`].
+    pub fn try_transform_with<T>(
+        data: &[T],
+        width: usize,
+        height: usize,
+        levels: usize,
+        decomposition: Decomposition,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        let floats = data.iter().map(|v| (*v).into()).collect();
+        Self::try_transform_in_place_with(floats, width, height, levels, decomposition)
+    }
+
+    /// Perform `levels` levels of the 2-D transform in place, or return an error as in
+    /// [`FilterWavelet2D::try_transform`].
+    pub fn try_transform_in_place(
+        data: Vec<f64>,
+        width: usize,
+        height: usize,
+        levels: usize,
+    ) -> Result<Self, WaveletError> {
+        Self::try_transform_in_place_with(data, width, height, levels, Decomposition::default())
+    }
+
+    /// Perform `levels` levels of the 2-D transform in place using the given decomposition, or
+    /// return an error as in [`FilterWavelet2D::try_transform`].
+    pub fn try_transform_in_place_with(
+        data: Vec<f64>,
+        width: usize,
+        height: usize,
+        levels: usize,
+        decomposition: Decomposition,
+    ) -> Result<Self, WaveletError> {
+        check_dimensions(&data, width, height)?;
+        let max = Self::max_levels(width, height);
+        if levels > max {
+            return Err(WaveletError::LevelTooDeep {
+                requested: levels,
+                max,
+            });
+        }
+
+        let bank = F::filter_bank();
+        let horizontal = Axis::new(&bank, width, levels);
+        let vertical = Axis::new(&bank, height, levels);
+        let stride = horizontal.size();
+        let mut coefficients = spread(data, width, height, stride, vertical.size());
+
+        let mut grid = Grid::new(&mut coefficients, stride);
+        let transform = |line: &mut Vec<f64>| wavelet::dwt_in_place(&bank, line, levels);
+        match decomposition {
+            Decomposition::Standard => {
+                grid.map_rows(height, &[(0, width)], &[(0, stride)], transform);
+                grid.map_columns(stride, &[(0, height)], &[(0, vertical.size())], transform);
+            }
+            Decomposition::NonStandard => {
+                let transform = |_, line: &mut Vec<f64>| wavelet::dwt_in_place(&bank, line, 1);
+                grid.transform_levels(&horizontal, &vertical, levels, transform, transform);
+            }
+        }
+        Ok(FilterWavelet2D {
+            transform: coefficients,
+            width,
+            height,
+            levels,
+            decomposition,
+            filter: PhantomData,
+        })
+    }
+
+    /// The most levels of the transform that data of these dimensions can support.
+    ///
+    /// This is the number of levels of the 1-D transform that the shorter axis supports.
+    pub fn max_levels(width: usize, height: usize) -> usize {
+        let bank = F::filter_bank();
+        let mode = BoundaryMode::Periodization;
+        wavelet::max_levels(&bank, width, mode).min(wavelet::max_levels(&bank, height, mode))
+    }
+
+    /// Invert the transform, leaving this instance unaffected.
+    pub fn invert(&self) -> Vec<f64> {
+        let mut data = self.transform.clone();
+        self.invert_data(&mut data);
+        data
+    }
+
+    /// Invert the transform in place, moving the underlying memory into the return value.
+    pub fn invert_in_place(&mut self) -> Vec<f64> {
+        let mut data = std::mem::take(&mut self.transform);
+        self.invert_data(&mut data);
+        data
+    }
+
+    fn invert_data(&self, data: &mut Vec<f64>) {
+        let bank = F::filter_bank();
+        let (width, height, levels) = (self.width, self.height, self.levels);
+        let (horizontal, vertical) = self.axes();
+        let stride = horizontal.size();
+        let mut grid = Grid::new(data, stride);
+        match self.decomposition {
+            Decomposition::Standard => {
+                let bank = &bank;
+                let inverse = |len| {
+                    move |line: &mut Vec<f64>| {
+                        wavelet::inverse_dwt_in_place(bank, line, len, levels)
+                    }
+                };
+                grid.map_columns(
+                    stride,
+                    &[(0, vertical.size())],
+                    &[(0, height)],
+                    inverse(height),
+                );
+                grid.map_rows(height, &[(0, stride)], &[(0, width)], inverse(width));
+            }
+            Decomposition::NonStandard => {
+                let inverse = |_, len, line: &mut Vec<f64>| {
+                    wavelet::inverse_dwt_in_place(&bank, line, len, 1)
+                };
+                grid.invert_levels(&horizontal, &vertical, levels, inverse, inverse);
+            }
+        }
+        compact(data, width, height, stride);
+    }
+
+    /// The bands of the transform along the rows and along the columns.
+    fn axes(&self) -> (Axis, Axis) {
+        let bank = F::filter_bank();
+        (
+            Axis::new(&bank, self.width, self.levels),
+            Axis::new(&bank, self.height, self.levels),
+        )
+    }
+
+    /// The transform coefficients, in row-major order, with as many to a row as the width given
+    /// by [`FilterWavelet2D::coefficients_size`].
+    pub fn coefficients(&self) -> &[f64] {
+        &self.transform
+    }
+
+    /// The width and height of the grid of coefficients. These are the width and height of the
+    /// data, unless a level had an odd number of samples along that axis.
+    pub fn coefficients_size(&self) -> (usize, usize) {
+        let (horizontal, vertical) = self.axes();
+        (horizontal.size(), vertical.size())
+    }
+
+    /// The width of the data.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the data.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// How the levels of the transform are arranged.
+    pub fn decomposition(&self) -> Decomposition {
+        self.decomposition
+    }
+
+    /// The width and height of the subbands at `level`, where level 1 is the finest.
+    pub fn subband_size(&self, level: usize) -> (usize, usize) {
+        let bank = F::filter_bank();
+        let mode = BoundaryMode::Periodization;
+        (
+            wavelet::level_lengths(&bank, self.width, mode, level)[level],
+            wavelet::level_lengths(&bank, self.height, mode, level)[level],
+        )
+    }
+
+    /// A copy of the subband at `level` (where level 1 is the finest), in row-major order.
+    ///
+    /// Only the coarsest level has an `LL` subband, since the `LL` subband of every other level
+    /// is what the next level transforms. This returns `None` if there's no such subband, or if
+    /// this is a [`Decomposition::Standard`] transform, which has no subbands.
+    pub fn subband(&self, level: usize, orientation: Orientation) -> Option<Vec<f64>> {
+        if self.decomposition != Decomposition::NonStandard {
+            return None;
+        }
+        let (horizontal, vertical) = self.axes();
+        let (x, y, width, height) = subband_bounds(&horizontal, &vertical, level, orientation)?;
+        Some(self.copy_region(x, y, width, height))
+    }
+
+    /// A copy of the block of a [`Decomposition::Standard`] transform which holds the
+    /// `horizontal` band of the rows' transform and the `vertical` band of the columns'
+    /// transform, in row-major order.
+    ///
+    /// This returns `None` if there's no such band, or if this is a
+    /// [`Decomposition::NonStandard`] transform.
+    pub fn block(&self, horizontal: Band, vertical: Band) -> Option<Vec<f64>> {
+        if self.decomposition != Decomposition::Standard {
+            return None;
+        }
+        let (columns, rows) = self.axes();
+        let (x, width) = columns.band(horizontal)?;
+        let (y, height) = rows.band(vertical)?;
+        Some(self.copy_region(x, y, width, height))
+    }
+
+    fn copy_region(&self, x: usize, y: usize, width: usize, height: usize) -> Vec<f64> {
+        (y..y + height)
+            .flat_map(|row| {
+                let start = row * self.coefficients_size().0 + x;
+                self.transform[start..start + width].iter().copied()
+            })
+            .collect()
+    }
+}
+
+/// The bands of the 1-D transform along one axis of the grid.
+pub(crate) struct Axis {
+    /// The number of samples at each level, as given by [`wavelet::level_lengths`].
+    pub(crate) lengths: Vec<usize>,
+}
+
+impl Axis {
+    pub(crate) fn new(bank: &FilterBank, len: usize, levels: usize) -> Axis {
+        Axis {
+            lengths: wavelet::level_lengths(bank, len, BoundaryMode::Periodization, levels),
+        }
+    }
+
+    /// The total number of coefficients along the axis.
+    pub(crate) fn size(&self) -> usize {
+        self.lengths[1..].iter().sum::<usize>() + self.lengths[self.lengths.len() - 1]
+    }
+
+    /// The position and length of a band along the axis.
+    pub(crate) fn band(&self, band: Band) -> Option<(usize, usize)> {
+        let levels = self.lengths.len() - 1;
+        match band {
+            Band::Average => Some((0, self.lengths[levels])),
+            Band::Detail(level) if (1..=levels).contains(&level) => {
+                let coarser: usize = self.lengths[level + 1..].iter().sum();
+                Some((self.lengths[levels] + coarser, self.lengths[level]))
+            }
+            Band::Detail(_) => None,
+        }
+    }
+
+    /// Where a single level of the non-standard decomposition puts its averages and its
+    /// differences along the axis, as `(start, len)`.
+    fn split(&self, level: usize) -> [(usize, usize); 2] {
+        let (start, len) = self.band(Band::Detail(level)).unwrap_or_default();
+        [(0, len), (start, len)]
+    }
+}
+
+/// Check that the data holds a `width` by `height` grid.
+pub(crate) fn check_dimensions(
+    data: &[f64],
+    width: usize,
+    height: usize,
+) -> Result<(), WaveletError> {
+    if data.len() != width * height {
+        return Err(WaveletError::InvalidLength {
+            expected: width * height,
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+/// The position `(x, y)` and size `(width, height)` of a subband within the transform.
+pub(crate) fn subband_bounds(
+    horizontal: &Axis,
+    vertical: &Axis,
+    level: usize,
+    orientation: Orientation,
+) -> Option<(usize, usize, usize, usize)> {
+    let levels = horizontal.lengths.len() - 1;
+    let valid = match orientation {
+        Orientation::LL => level == levels,
+        _ => (1..=levels).contains(&level),
+    };
+    if !valid {
+        return None;
+    }
+    let (x, w) = horizontal.band(Band::Detail(level))?;
+    let (y, h) = vertical.band(Band::Detail(level))?;
+    let (x, y) = match orientation {
+        Orientation::LL => (0, 0),
+        Orientation::HL => (x, 0),
+        Orientation::LH => (0, y),
+        Orientation::HH => (x, y),
+    };
+    Some((x, y, w, h))
+}
+
+/// Lay out a `width` by `height` grid as the first rows and columns of a `stride` by `rows` grid
+/// of zeros, reusing the data if the grids are the same size.
+pub(crate) fn spread(
+    data: Vec<f64>,
+    width: usize,
+    height: usize,
+    stride: usize,
+    rows: usize,
+) -> Vec<f64> {
+    if (stride, rows) == (width, height) {
+        return data;
+    }
+    // the coefficients need more room than the data, so spread it out
+    let mut coefficients = vec![0.0; stride * rows];
+    for (row, samples) in data.chunks(width.max(1)).enumerate().take(height) {
+        coefficients[row * stride..row * stride + width].copy_from_slice(samples);
+    }
+    coefficients
+}
+
+/// Undo [`spread`], dropping the extra coefficients from the ends of the rows and columns.
+pub(crate) fn compact(data: &mut Vec<f64>, width: usize, height: usize, stride: usize) {
+    for row in 0..height {
+        data.copy_within(row * stride..row * stride + width, row * width);
+    }
+    data.truncate(width * height);
+}
+
+/// A row-major grid of data, with a scratch line for transforming its rows and columns.
+///
+/// Each row or column is moved into the scratch line (leaving zeros behind), transformed in place
+/// there, and moved back, so the only extra memory needed is a single line. A line can be read
+/// from, and written back to, several segments `(start, len)` of the row or column, so that a
+/// level can put its averages and its differences where the layout of the transform needs them.
+pub(crate) struct Grid<'a> {
+    data: &'a mut [f64],
+    stride: usize,
+    line: Vec<f64>,
+}
+
+impl<'a> Grid<'a> {
+    pub(crate) fn new(data: &'a mut [f64], stride: usize) -> Grid<'a> {
+        Grid {
+            data,
+            stride,
+            line: Vec::new(),
+        }
+    }
+
+    /// Perform `levels` levels of the non-standard decomposition of the grid with the bands of
+    /// `horizontal` and `vertical`, where `rows(level, line)` and `columns(level, line)` perform
+    /// a single level of the 1-D transform (level 1 being the first) on a row or a column.
+    ///
+    /// Each level reads the averages of the level before, and writes its averages and its
+    /// differences where the layout of the whole transform needs them (see [`Axis::split`]).
+    pub(crate) fn transform_levels<R, C>(
+        &mut self,
+        horizontal: &Axis,
+        vertical: &Axis,
+        levels: usize,
+        rows: R,
+        columns: C,
+    ) where
+        R: Fn(usize, &mut Vec<f64>),
+        C: Fn(usize, &mut Vec<f64>),
+    {
+        for level in 1..=levels {
+            let (w, h) = (horizontal.lengths[level - 1], vertical.lengths[level - 1]);
+            let (split_columns, split_rows) = (horizontal.split(level), vertical.split(level));
+            self.map_rows(h, &[(0, w)], &split_columns, |line| rows(level, line));
+            for (start, len) in split_columns {
+                for x in start..start + len {
+                    self.map_columns_at(x, &[(0, h)], &split_rows, |line| columns(level, line));
+                }
+            }
+        }
+    }
+
+    /// Undo [`Grid::transform_levels`], where `rows(level, len, line)` and
+    /// `columns(level, len, line)` invert a single level of the 1-D transform of `len` samples.
+    pub(crate) fn invert_levels<R, C>(
+        &mut self,
+        horizontal: &Axis,
+        vertical: &Axis,
+        levels: usize,
+        rows: R,
+        columns: C,
+    ) where
+        R: Fn(usize, usize, &mut Vec<f64>),
+        C: Fn(usize, usize, &mut Vec<f64>),
+    {
+        for level in (1..=levels).rev() {
+            let (w, h) = (horizontal.lengths[level - 1], vertical.lengths[level - 1]);
+            let (split_columns, split_rows) = (horizontal.split(level), vertical.split(level));
+            for (start, len) in split_columns {
+                for x in start..start + len {
+                    self.map_columns_at(x, &split_rows, &[(0, h)], |line| columns(level, h, line));
+                }
+            }
+            self.map_rows(h, &split_columns, &[(0, w)], |line| rows(level, w, line));
+        }
+    }
+
+    /// Apply `op` to each of the first `count` rows, reading it from the segments `from` and
+    /// writing the result to the segments `to`.
+    fn map_rows<Op>(&mut self, count: usize, from: &[(usize, usize)], to: &[(usize, usize)], op: Op)
+    where
+        Op: Fn(&mut Vec<f64>),
+    {
+        for y in 0..count {
+            self.map_line(y * self.stride, 1, from, to, &op);
+        }
+    }
+
+    /// Apply `op` to each of the first `count` columns, as in [`Grid::map_rows`].
+    fn map_columns<Op>(
+        &mut self,
+        count: usize,
+        from: &[(usize, usize)],
+        to: &[(usize, usize)],
+        op: Op,
+    ) where
+        Op: Fn(&mut Vec<f64>),
+    {
+        for x in 0..count {
+            self.map_columns_at(x, from, to, &op);
+        }
+    }
+
+    /// Apply `op` to column `x`, as in [`Grid::map_rows`].
+    fn map_columns_at<Op>(
+        &mut self,
+        x: usize,
+        from: &[(usize, usize)],
+        to: &[(usize, usize)],
+        op: Op,
+    ) where
+        Op: Fn(&mut Vec<f64>),
+    {
+        self.map_line(x, self.stride, from, to, &op);
+    }
+
+    fn map_line<Op>(
+        &mut self,
+        start: usize,
+        step: usize,
+        from: &[(usize, usize)],
+        to: &[(usize, usize)],
+        op: &Op,
+    ) where
+        Op: Fn(&mut Vec<f64>),
+    {
+        self.line.clear();
+        for &(first, len) in from {
+            for i in first..first + len {
+                let value = std::mem::take(&mut self.data[start + i * step]);
+                self.line.push(value);
+            }
+        }
+        op(&mut self.line);
+        let positions = to.iter().flat_map(|&(first, len)| first..first + len);
+        for (i, value) in positions.zip(&self.line) {
+            self.data[start + i * step] = *value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daubechies::Daub4;
+    use crate::haar::HaarWavelet;
+    use crate::WaveletTransform;
+
+    fn image(width: usize, height: usize) -> Vec<f64> {
+        (0..width * height)
+            .map(|i| ((i * 37) % 23) as f64 - 11.0 + (i / width) as f64)
+            .collect()
+    }
+
+    #[test]
+    fn haar_level_matches_hand_computation() {
+        // a single 2x2 block [a b; c d] has LL = (a+b+c+d)/2, HL = (a-b+c-d)/2,
+        // LH = (a+b-c-d)/2 and HH = (a-b-c+d)/2
+        let wavelet = HaarWavelet2D::transform(&[1.0, 2.0, 3.0, 5.0], 2, 2);
+
+        let expected = [5.5, -1.5, -2.5, 0.5];
+        for (pos, (expected, actual)) in expected.iter().zip(wavelet.coefficients()).enumerate() {
+            assert!(
+                (expected - actual).abs() < 1e-14,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+        for (orientation, expected) in [(Orientation::HL, -1.5), (Orientation::LH, -2.5)] {
+            let band = wavelet.subband(1, orientation).unwrap();
+            assert_eq!(1, band.len());
+            assert!((expected - band[0]).abs() < 1e-14, "{:?}", orientation);
+        }
+    }
+
+    #[test]
+    fn inverts() {
+        for (width, height) in [(8, 8), (16, 4), (12, 20), (5, 7), (13, 6), (31, 17)] {
+            let data = image(width, height);
+            let wavelet = FilterWavelet2D::<Daub4>::transform(&data, width, height);
+            assert_eq!(
+                FilterWavelet2D::<Daub4>::max_levels(width, height),
+                wavelet.levels()
+            );
+            let inverse = wavelet.invert();
+            for (pos, expected) in data.iter().enumerate() {
+                assert!(
+                    (expected - inverse[pos]).abs() < 1e-12,
+                    "Element at pos {} incorrect. Expected {} but was {}",
+                    pos,
+                    expected,
+                    inverse[pos]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn subbands_separate_edges() {
+        // vertical stripes have horizontal differences, but no vertical ones
+        let stripes: Vec<f64> = (0..64).map(|i| ((i % 8) % 2) as f64).collect();
+        let wavelet = HaarWavelet2D::try_transform(&stripes, 8, 8, 1).unwrap();
+
+        let hl = wavelet.subband(1, Orientation::HL).unwrap();
+        assert_eq!((4, 4), wavelet.subband_size(1));
+        assert_eq!(16, hl.len());
+        assert!(
+            hl.iter().all(|v| v.abs() > 0.5),
+            "HL should hold the stripes"
+        );
+        for orientation in [Orientation::LH, Orientation::HH] {
+            let band = wavelet.subband(1, orientation).unwrap();
+            assert!(band.iter().all(|v| v.abs() < 1e-14), "{:?}", orientation);
+        }
+        assert!(wavelet.subband(2, Orientation::HL).is_none());
+        assert!(wavelet.subband(1, Orientation::LL).is_some());
+    }
+
+    #[test]
+    fn both_decompositions_invert() {
+        for decomposition in [Decomposition::Standard, Decomposition::NonStandard] {
+            for (width, height) in [(8, 8), (16, 4), (4, 32), (5, 7), (9, 12)] {
+                let data = image(width, height);
+                let mut wavelet =
+                    HaarWavelet2D::transform_with(&data, width, height, decomposition);
+                assert_eq!(decomposition, wavelet.decomposition());
+                let inverse = wavelet.invert_in_place();
+                for (pos, expected) in data.iter().enumerate() {
+                    assert!(
+                        (expected - inverse[pos]).abs() < 1e-12,
+                        "{:?} element at pos {} incorrect. Expected {} but was {}",
+                        decomposition,
+                        pos,
+                        expected,
+                        inverse[pos]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn standard_decomposition_is_a_tensor_product() {
+        // the standard decomposition of f(x)g(y) is the product of the 1-D transforms of f and g
+        let f = [3.0, -1.0, 4.0, 1.0, -5.0, 9.0, 2.0, 6.0];
+        let g = [2.0, 7.0, -1.0, 8.0, 2.0, 8.0, -1.0, 8.0];
+        let data: Vec<f64> = g
+            .iter()
+            .flat_map(|y| f.iter().map(move |x| x * y))
+            .collect();
+        let wavelet = HaarWavelet2D::transform_with(&data, 8, 8, Decomposition::Standard);
+        let non_standard = HaarWavelet2D::transform(&data, 8, 8);
+
+        let rows = HaarWavelet::transform(&f);
+        let columns = HaarWavelet::transform(&g);
+        for (pos, actual) in wavelet.coefficients().iter().enumerate() {
+            let expected = rows.coefficients()[pos % 8] * columns.coefficients()[pos / 8];
+            assert!(
+                (expected - actual).abs() < 1e-12,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+        assert!(non_standard
+            .coefficients()
+            .iter()
+            .zip(wavelet.coefficients())
+            .any(|(a, b)| (a - b).abs() > 1e-6));
+
+        let block = wavelet.block(Band::Detail(1), Band::Average).unwrap();
+        assert_eq!(4, block.len());
+        assert!(wavelet.block(Band::Detail(4), Band::Average).is_none());
+        assert!(wavelet.subband(1, Orientation::HH).is_none());
+        assert!(non_standard.block(Band::Average, Band::Average).is_none());
+    }
+
+    #[test]
+    fn transforms_any_dimensions() {
+        // the standard decomposition is still the tensor product of the 1-D transforms, which
+        // now have more coefficients than samples
+        let f = [3.0, -1.0, 4.0, 1.0, -5.0];
+        let g = [2.0, 7.0, -1.0, 8.0, 2.0, 8.0, -1.0];
+        let data: Vec<f64> = g
+            .iter()
+            .flat_map(|y| f.iter().map(move |x| x * y))
+            .collect();
+        let wavelet = HaarWavelet2D::transform_with(&data, 5, 7, Decomposition::Standard);
+        let rows = HaarWavelet::transform(&f);
+        let columns = HaarWavelet::transform(&g);
+        let (width, height) = wavelet.coefficients_size();
+        assert_eq!(rows.coefficients().len(), width);
+        assert_eq!(columns.coefficients().len(), height);
+        for (pos, actual) in wavelet.coefficients().iter().enumerate() {
+            let expected = rows.coefficients()[pos % width] * columns.coefficients()[pos / width];
+            assert!(
+                (expected - actual).abs() < 1e-12,
+                "Coefficient {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                actual
+            );
+        }
+
+        // a single level of the non-standard decomposition transforms rows then columns
+        let wavelet = HaarWavelet2D::try_transform(&data, 5, 7, 1).unwrap();
+        assert_eq!((3, 4), wavelet.subband_size(1));
+        let hh = wavelet.subband(1, Orientation::HH).unwrap();
+        let row_details = |row: &[f64]| {
+            HaarWavelet::try_transform_with(row, BoundaryMode::Periodization, 1).unwrap()
+        };
+        for (pos, actual) in hh.iter().enumerate() {
+            let (x, y) = (pos % 3, pos / 3);
+            let expected =
+                row_details(&f).coefficients()[3 + x] * row_details(&g).coefficients()[4 + y];
+            assert!((expected - actual).abs() < 1e-12, "HH {} incorrect", pos);
+        }
+    }
+
+    #[test]
+    fn reports_invalid_dimensions() {
+        assert_eq!(
+            WaveletError::InvalidLength {
+                expected: 12,
+                actual: 10
+            },
+            HaarWavelet2D::try_transform(&[0.0; 10], 3, 4, 0).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::LevelTooDeep {
+                requested: 3,
+                max: 2
+            },
+            HaarWavelet2D::try_transform(&[0.0; 24], 6, 4, 3).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,895 @@
+// The discrete wavelet transform for any wavelet which can be described by a finite filter bank.
+use std::marker::PhantomData;
+
+use crate::arrays;
+use crate::boundary::BoundaryMode;
+use crate::error::{self, WaveletError};
+use crate::filter::{Filter, FilterBank};
+use crate::DetailCoefficients;
+
+/// The Discrete Wavelet Transform of a wavelet with compact support.
+///
+/// The forward transform uses the dual (analysis) filters of the filter bank, and the inverse
+/// uses the primal (synthesis) filters. For an orthogonal wavelet these are the same thing.
+///
+/// The transform is held in the standard layout `[avg | d^J | d^(J-1) | ... | d^1]`, where
+/// `avg` is the final average term and `d^j` are the difference terms of level `j` (so the
+/// finest differences occupy the back half of the array). The transform also remembers how many
+/// samples it was computed from, so that the inverse gives back exactly that many.
+#[derive(Debug)]
+pub struct FilterWavelet<F: Filter> {
+    transform: Vec<f64>,
+    len: usize,
+    mode: BoundaryMode,
+    levels: usize,
+    filter: PhantomData<F>,
+}
+
+/// The Discrete Wavelet Transform of an orthogonal wavelet.
+pub type OrthogonalWavelet<F> = FilterWavelet<F>;
+
+/// The Discrete Wavelet Transform of a biorthogonal wavelet.
+pub type BiorthogonalWavelet<F> = FilterWavelet<F>;
+
+impl<F: Filter> FilterWavelet<F> {
+    /// Perform the Discrete Wavelet Transform, extending the data beyond its ends according to
+    /// `mode`.
+    ///
+    /// The data can be any length. [`BoundaryMode::Periodization`] produces as many coefficients as
+    /// there are samples (plus one for each level with an odd number of samples), and the other
+    /// modes produce a few more.
+    pub fn transform_with<T>(data: &[T], mode: BoundaryMode) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let levels = Self::max_levels(data.len(), mode);
+        Self::transform_levels(data, mode, levels)
+    }
+
+    /// Perform the first `levels` levels of the Discrete Wavelet Transform, extending the data
+    /// beyond its ends according to `mode`.
+    ///
+    /// This returns an error if the data is not finite, or if it can't support that many levels.
+    pub fn try_transform_with<T>(
+        data: &[T],
+        mode: BoundaryMode,
+        levels: usize,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        check_levels(&F::filter_bank(), data.len(), mode, levels)?;
+        Ok(Self::transform_levels(data, mode, levels))
+    }
+
+    /// Reassemble a transform from its coefficients, as returned by
+    /// [`FilterWavelet::coefficients`].
+    ///
+    /// `len`, `mode` and `levels` must be the same as the transform was computed with, and this
+    /// returns an error if the number of coefficients doesn't match them.
+    pub fn try_from_coefficients(
+        coefficients: Vec<f64>,
+        len: usize,
+        mode: BoundaryMode,
+        levels: usize,
+    ) -> Result<Self, WaveletError> {
+        let bank = F::filter_bank();
+        check_levels(&bank, len, mode, levels)?;
+        let expected = coefficients_len(&bank, len, mode, levels);
+        if coefficients.len() != expected {
+            return Err(WaveletError::InvalidLength {
+                expected,
+                actual: coefficients.len(),
+            });
+        }
+        Ok(FilterWavelet {
+            transform: coefficients,
+            len,
+            mode,
+            levels,
+            filter: PhantomData,
+        })
+    }
+
+    /// The most levels of the transform that `len` samples can support using `mode`.
+    pub fn max_levels(len: usize, mode: BoundaryMode) -> usize {
+        max_levels(&F::filter_bank(), len, mode)
+    }
+
+    fn transform_levels<T>(data: &[T], mode: BoundaryMode, levels: usize) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let floats: Vec<f64> = data.iter().map(|v| (*v).into()).collect();
+        FilterWavelet {
+            transform: dwt(&F::filter_bank(), &floats, mode, levels),
+            len: floats.len(),
+            mode,
+            levels,
+            filter: PhantomData,
+        }
+    }
+
+    /// The transform coefficients.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.transform
+    }
+
+    /// The boundary mode used to compute the transform.
+    pub fn mode(&self) -> BoundaryMode {
+        self.mode
+    }
+
+    /// The number of levels of the transform.
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+}
+
+impl<F: Filter> super::WaveletTransform for FilterWavelet<F> {
+    fn transform<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::transform_with(data, BoundaryMode::default())
+    }
+
+    fn transform_in_place(mut data: Vec<f64>) -> Self {
+        let len = data.len();
+        let levels = Self::max_levels(len, BoundaryMode::Periodization);
+        dwt_in_place(&F::filter_bank(), &mut data, levels);
+        FilterWavelet {
+            len,
+            transform: data,
+            mode: BoundaryMode::Periodization,
+            levels,
+            filter: PhantomData,
+        }
+    }
+
+    fn invert(&self) -> Vec<f64> {
+        inverse_dwt(
+            &F::filter_bank(),
+            &self.transform,
+            self.len,
+            self.mode,
+            self.levels,
+        )
+    }
+
+    fn invert_in_place(&mut self) -> Vec<f64> {
+        if self.mode != BoundaryMode::Periodization {
+            // the inverse of an expansive transform is smaller than the transform, so there's no
+            // in place algorithm to speak of
+            let data = self.invert();
+            self.transform = vec![];
+            return data;
+        }
+        inverse_dwt_in_place(
+            &F::filter_bank(),
+            &mut self.transform,
+            self.len,
+            self.levels,
+        );
+        std::mem::take(&mut self.transform)
+    }
+}
+
+impl<F: Filter> DetailCoefficients for FilterWavelet<F> {
+    fn detail_levels(&self) -> usize {
+        self.levels
+    }
+
+    fn detail_coefficients(&self, level: usize) -> Option<&[f64]> {
+        let range = self.detail_range(level)?;
+        Some(&self.transform[range])
+    }
+
+    fn detail_coefficients_mut(&mut self, level: usize) -> Option<&mut [f64]> {
+        let range = self.detail_range(level)?;
+        Some(&mut self.transform[range])
+    }
+
+    fn signal_len(&self) -> usize {
+        self.len
+    }
+
+    fn noise_gain(&self, level: usize) -> f64 {
+        F::filter_bank().analysis_gain(level)
+    }
+}
+
+impl<F: Filter> FilterWavelet<F> {
+    /// Where the differences `d^level` are in the layout `[avg | d^J | ... | d^1]`.
+    fn detail_range(&self, level: usize) -> Option<std::ops::Range<usize>> {
+        if level == 0 || level > self.levels {
+            return None;
+        }
+        let lengths = level_lengths(&F::filter_bank(), self.len, self.mode, self.levels);
+        let end = self.transform.len() - lengths[1..level].iter().sum::<usize>();
+        Some(end - lengths[level]..end)
+    }
+}
+
+/// Compute the averages and differences of a single level of the transform.
+///
+/// The samples beyond the ends of `src` are determined by `mode`, and
+/// `avg[k] = sum_n ~h_n src[2k+n+s]`, `diff[k] = sum_n ~g_n src[2k+n+s]` (using the dual filters).
+/// For [`BoundaryMode::Periodization`] the offset `s` is zero, and the filter just wraps around the
+/// end of the array (after repeating the last sample, if there are an odd number of them). For the
+/// expansive modes `s = 2-L`, so that the first coefficient is the first one which overlaps the
+/// data.
+fn level_dwt(
+    bank: &FilterBank,
+    src: &[f64],
+    mode: BoundaryMode,
+    avg: &mut [f64],
+    diff: &mut [f64],
+) {
+    let offset = level_offset(bank, mode);
+    let period = 2 * avg.len();
+    for k in 0..avg.len() {
+        let mut a = 0.0;
+        let mut d = 0.0;
+        for (n, (h, g)) in bank
+            .dual_scaling()
+            .iter()
+            .zip(bank.dual_wavelet())
+            .enumerate()
+        {
+            let v = match mode {
+                BoundaryMode::Periodization => src[((2 * k + n) % period).min(src.len() - 1)],
+                _ => mode.sample(src, (2 * k + n) as isize + offset),
+            };
+            a += h * v;
+            d += g * v;
+        }
+        avg[k] = a;
+        diff[k] = d;
+    }
+}
+
+/// Undo a single level of the transform, accumulating the result into `dest`.
+///
+/// This is the transpose of [`level_dwt`] with the dual filters replaced by the primal ones, so
+/// `dest[2k+n+s] += h_n avg[k] + g_n diff[k]`. With [`BoundaryMode::Periodization`] this wraps
+/// around the end of the (evenly extended) array again. The terms which land outside of `dest`
+/// belong to the extension, so they're dropped.
+fn level_inverse_dwt(
+    bank: &FilterBank,
+    avg: &[f64],
+    diff: &[f64],
+    mode: BoundaryMode,
+    dest: &mut [f64],
+) {
+    let len = dest.len() as isize;
+    let period = 2 * avg.len() as isize;
+    let offset = level_offset(bank, mode);
+    dest.iter_mut().for_each(|v| *v = 0.0);
+    for k in 0..avg.len() {
+        for (n, (h, g)) in bank.scaling().iter().zip(bank.wavelet()).enumerate() {
+            let pos = (2 * k + n) as isize + offset;
+            let pos = match mode {
+                BoundaryMode::Periodization => pos.rem_euclid(period),
+                _ => pos,
+            };
+            if (0..len).contains(&pos) {
+                dest[pos as usize] += h * avg[k] + g * diff[k];
+            }
+        }
+    }
+}
+
+/// The position of the first sample read by the filters, relative to `2k`.
+fn level_offset(bank: &FilterBank, mode: BoundaryMode) -> isize {
+    match mode {
+        BoundaryMode::Periodization => 0,
+        _ => 2 - bank.len() as isize,
+    }
+}
+
+/// The most levels of the transform that `len` samples can support.
+///
+/// With [`BoundaryMode::Periodization`], each level halves the data (rounding up), all the way down
+/// to a single average. The expansive modes can't get the data any smaller than the filter, so they
+/// stop at the deepest level whose data is at least as long as the filter is (roughly).
+pub(crate) fn max_levels(bank: &FilterBank, len: usize, mode: BoundaryMode) -> usize {
+    match mode {
+        BoundaryMode::Periodization if len > 1 => (len - 1).ilog2() as usize + 1,
+        BoundaryMode::Periodization => 0,
+        _ => (len / (bank.len() - 1)).checked_ilog2().unwrap_or(0) as usize,
+    }
+}
+
+/// Check that `len` samples can support `levels` levels of the transform.
+fn check_levels(
+    bank: &FilterBank,
+    len: usize,
+    mode: BoundaryMode,
+    levels: usize,
+) -> Result<(), WaveletError> {
+    let max = max_levels(bank, len, mode);
+    if levels > max {
+        return Err(WaveletError::LevelTooDeep {
+            requested: levels,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// The length of the data at each of the first `levels` levels of the transform of `len`
+/// samples, starting with `len` itself, and ending with the number of final averages.
+pub(crate) fn level_lengths(
+    bank: &FilterBank,
+    len: usize,
+    mode: BoundaryMode,
+    levels: usize,
+) -> Vec<usize> {
+    let mut lengths = vec![len];
+    for _ in 0..levels {
+        let last = lengths[lengths.len() - 1];
+        lengths.push(mode.coefficients_len(last, bank.len()));
+    }
+    lengths
+}
+
+/// The total number of coefficients in the transform of `len` samples.
+fn coefficients_len(bank: &FilterBank, len: usize, mode: BoundaryMode, levels: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let lengths = level_lengths(bank, len, mode, levels);
+    lengths[1..].iter().sum::<usize>() + lengths[lengths.len() - 1]
+}
+
+/// Perform `levels` levels of the Discrete Wavelet Transform.
+///
+/// The coefficients are laid out as `[avg | d^J | ... | d^1]`, where the lengths of each part are
+/// given by [`level_lengths`].
+pub(crate) fn dwt(bank: &FilterBank, data: &[f64], mode: BoundaryMode, levels: usize) -> Vec<f64> {
+    if data.is_empty() {
+        return vec![]; //nothing to do
+    }
+
+    let lengths = level_lengths(bank, data.len(), mode, levels);
+    let mut end = coefficients_len(bank, data.len(), mode, levels);
+    let mut transform = vec![0.0; end];
+    let mut src: Vec<f64> = data.to_vec();
+    for &len in &lengths[1..] {
+        let mut avg = vec![0.0; len];
+        level_dwt(bank, &src, mode, &mut avg, &mut transform[end - len..end]);
+        end -= len;
+        src = avg;
+    }
+    transform[..end].copy_from_slice(&src);
+    transform
+}
+
+/// Invert `levels` levels of the Discrete Wavelet Transform of `len` samples.
+pub(crate) fn inverse_dwt(
+    bank: &FilterBank,
+    wavelet: &[f64],
+    len: usize,
+    mode: BoundaryMode,
+    levels: usize,
+) -> Vec<f64> {
+    if wavelet.is_empty() {
+        return vec![]; //nothing to do
+    }
+
+    let lengths = level_lengths(bank, len, mode, levels);
+    let mut start = lengths[lengths.len() - 1];
+    let mut transformed = wavelet[..start].to_vec();
+    for pair in lengths.windows(2).rev() {
+        let (len, split) = (pair[0], pair[1]);
+        let mut step = vec![0.0; len];
+        level_inverse_dwt(
+            bank,
+            &transformed,
+            &wavelet[start..start + split],
+            mode,
+            &mut step,
+        );
+        start += split;
+        transformed = step;
+    }
+    transformed
+}
+
+/// Perform the Discrete Wavelet Transform in place.
+///
+/// This works in the same way as the Haar cascade: each level computes its averages and
+/// differences into adjacent positions `2k` and `2k+1` of the array, and then the averages are
+/// permuted to the front and the differences to the back using [`arrays::partition_evens`].
+///
+/// Computing the pair for position `k` reads the samples `2k..2k+L`, none of which have been
+/// overwritten yet--except where the filter wraps around the end of the array and reads samples
+/// from the front. So the only extra memory we need is a copy of the first `L` samples at each
+/// level, which is independent of the size of the data.
+///
+/// When a level has an odd number of samples, the last one is repeated to make it even (as
+/// [`level_dwt`] does). That needs one more slot in the array, which is why this takes a `Vec`--but
+/// the insertion only shuffles the elements which are already there along by one.
+pub(crate) fn dwt_in_place(bank: &FilterBank, data: &mut Vec<f64>, levels: usize) {
+    let mut len = data.len();
+    for _ in 0..levels {
+        if !len.is_multiple_of(2) {
+            data.insert(len, data[len - 1]);
+            len += 1;
+        }
+        let wrap = data[..len.min(bank.len())].to_vec();
+        for k in 0..len / 2 {
+            let mut a = 0.0;
+            let mut d = 0.0;
+            for (n, (h, g)) in bank
+                .dual_scaling()
+                .iter()
+                .zip(bank.dual_wavelet())
+                .enumerate()
+            {
+                let p = 2 * k + n;
+                let v = if p < len { data[p] } else { wrap[p % len] };
+                a += h * v;
+                d += g * v;
+            }
+            data[2 * k] = a;
+            data[2 * k + 1] = d;
+        }
+        arrays::partition_evens(&mut data[..len]);
+
+        len /= 2;
+    }
+}
+
+/// Invert the Discrete Wavelet Transform in place.
+///
+/// Each level first interleaves the averages with their difference terms (the reverse of the
+/// permutation performed by [`dwt_in_place`]), so that the pair `(avg[k], diff[k])` is at
+/// positions `2k` and `2k+1`. The output pair `j` depends only on the coefficient pairs
+/// `j, j-1, ..., j-L/2+1`, so if we work backwards from the end of the array, the only pairs which
+/// have already been overwritten when we need them are the ones that wrap around from the end.
+/// We keep a copy of those `L/2` pairs, and otherwise operate in place.
+///
+/// `len` is the number of samples that the transform was computed from. Any samples which were
+/// repeated to make a level even are removed again afterwards.
+pub(crate) fn inverse_dwt_in_place(
+    bank: &FilterBank,
+    data: &mut Vec<f64>,
+    len: usize,
+    levels: usize,
+) {
+    if data.is_empty() {
+        return; //nothing to do
+    }
+
+    let half = bank.len() / 2;
+    let lengths = level_lengths(bank, len, BoundaryMode::Periodization, levels);
+    for level in lengths.windows(2).rev() {
+        let pairs = level[1];
+        let extended = 2 * pairs;
+        arrays::interleave(&mut data[..extended]);
+
+        let first_saved = pairs.saturating_sub(half);
+        let saved = data[2 * first_saved..extended].to_vec();
+        for j in (0..pairs).rev() {
+            let mut even = 0.0;
+            let mut odd = 0.0;
+            for s in 0..half {
+                // the coefficient pair which contributes to this output pair through h_2s and h_2s+1
+                let k = (j + pairs * half - s) % pairs;
+                let (a, d) = if k > j {
+                    let p = 2 * (k - first_saved);
+                    (saved[p], saved[p + 1])
+                } else {
+                    (data[2 * k], data[2 * k + 1])
+                };
+                even += bank.scaling()[2 * s] * a + bank.wavelet()[2 * s] * d;
+                odd += bank.scaling()[2 * s + 1] * a + bank.wavelet()[2 * s + 1] * d;
+            }
+            data[2 * j] = even;
+            data[2 * j + 1] = odd;
+        }
+
+        if level[0] < extended {
+            data.remove(level[0]);
+        }
+    }
+}
+
+/// A Multi-Resolution Decomposition using a wavelet with compact support.
+///
+/// Level `j` holds the averages and differences `[avg^(j+1) | d^(j+1)]` computed from the
+/// averages of the level before it.
+#[derive(Debug)]
+pub struct FilterDecomposition<F: Filter> {
+    levels: Vec<Vec<f64>>,
+    len: usize,
+    mode: BoundaryMode,
+    filter: PhantomData<F>,
+}
+
+/// A Multi-Resolution Decomposition using an orthogonal wavelet.
+pub type OrthogonalDecomposition<F> = FilterDecomposition<F>;
+
+/// A Multi-Resolution Decomposition using a biorthogonal wavelet.
+pub type BiorthogonalDecomposition<F> = FilterDecomposition<F>;
+
+impl<F: Filter> FilterDecomposition<F> {
+    /// Decompose the data, extending it beyond its ends according to `mode`.
+    ///
+    /// See [`FilterWavelet::transform_with`] for how the modes affect the size of each level.
+    pub fn decompose_with<T>(data: &[T], mode: BoundaryMode) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let levels = max_levels(&F::filter_bank(), data.len(), mode);
+        Self::decompose_levels(data, mode, levels)
+    }
+
+    /// Decompose the data into `levels` levels, extending it beyond its ends according to `mode`.
+    ///
+    /// This returns an error if the data is not finite, or if it can't support that many levels.
+    pub fn try_decompose_with<T>(
+        data: &[T],
+        mode: BoundaryMode,
+        levels: usize,
+    ) -> Result<Self, WaveletError>
+    where
+        T: Into<f64> + Copy,
+    {
+        error::check_finite(data)?;
+        check_levels(&F::filter_bank(), data.len(), mode, levels)?;
+        Ok(Self::decompose_levels(data, mode, levels))
+    }
+
+    fn decompose_levels<T>(data: &[T], mode: BoundaryMode, levels: usize) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let floats: Vec<f64> = data.iter().map(|v| (*v).into()).collect();
+        FilterDecomposition {
+            levels: decompose_multiresolution(&F::filter_bank(), &floats, mode, levels),
+            len: floats.len(),
+            mode,
+            filter: PhantomData,
+        }
+    }
+
+    /// The levels of the decomposition, from finest to coarsest.
+    pub fn levels(&self) -> &[Vec<f64>] {
+        &self.levels
+    }
+}
+
+impl<F: Filter> From<FilterDecomposition<F>> for FilterWavelet<F> {
+    fn from(decomposition: FilterDecomposition<F>) -> Self {
+        // Each level holds the averages and differences of the transform at that level, and the
+        // averages are what the next level decomposes. So the transform is the final averages,
+        // followed by the difference terms from coarsest to finest.
+        let levels = decomposition.levels;
+        let mut transform = Vec::with_capacity(levels.iter().map(|l| l.len() / 2).sum());
+        if let Some(last) = levels.last() {
+            transform.extend_from_slice(&last[..last.len() / 2]);
+        }
+        for level in levels.iter().rev() {
+            transform.extend_from_slice(&level[level.len() / 2..]);
+        }
+
+        FilterWavelet {
+            transform,
+            len: decomposition.len,
+            mode: decomposition.mode,
+            levels: levels.len(),
+            filter: PhantomData,
+        }
+    }
+}
+
+impl<F: Filter> super::MRDecomposition<FilterWavelet<F>> for FilterDecomposition<F> {
+    fn decompose<T>(data: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        Self::decompose_with(data, BoundaryMode::default())
+    }
+}
+
+/// Compute the first `levels` levels of the multi-resolution decomposition.
+pub(crate) fn decompose_multiresolution(
+    bank: &FilterBank,
+    data: &[f64],
+    mode: BoundaryMode,
+    levels: usize,
+) -> Vec<Vec<f64>> {
+    if data.is_empty() {
+        return vec![]; //nothing to do
+    }
+
+    let lengths = level_lengths(bank, data.len(), mode, levels);
+    let mut full_decomp: Vec<Vec<f64>> = Vec::with_capacity(lengths.len() - 1);
+    let mut src = data;
+    for &split in &lengths[1..] {
+        let mut decomp = vec![0.0; 2 * split];
+        let (avg, diff) = decomp.split_at_mut(split);
+        level_dwt(bank, src, mode, avg, diff);
+        full_decomp.push(decomp);
+        src = &full_decomp[full_decomp.len() - 1][..split];
+    }
+    full_decomp
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boundary::BoundaryMode;
+    use crate::filter::FilterBank;
+
+    fn daub4() -> FilterBank {
+        let r3 = 3_f64.sqrt();
+        let norm = 4.0 * std::f64::consts::SQRT_2;
+        FilterBank::orthogonal(&[
+            (1.0 + r3) / norm,
+            (3.0 + r3) / norm,
+            (3.0 - r3) / norm,
+            (1.0 - r3) / norm,
+        ])
+    }
+
+    const DATA: [f64; 16] = [
+        1.0, 3.0, 5.0, 11.0, 12.0, 13.0, 0.0, 1.0, -4.0, 2.5, 7.0, 7.0, 6.0, 0.5, 3.0, 9.0,
+    ];
+
+    #[test]
+    fn inverse_works() {
+        let bank = daub4();
+        let wavelet = super::dwt(&bank, &DATA, BoundaryMode::Periodization, 4);
+        let inverse =
+            super::inverse_dwt(&bank, &wavelet, DATA.len(), BoundaryMode::Periodization, 4);
+
+        let delta = 1e-12;
+        for (pos, expected) in DATA.iter().enumerate() {
+            assert!(
+                (expected - inverse[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                inverse[pos]
+            );
+        }
+    }
+
+    #[test]
+    fn transform_preserves_energy() {
+        let wavelet = super::dwt(&daub4(), &DATA, BoundaryMode::Periodization, 4);
+
+        let expected: f64 = DATA.iter().map(|v| v * v).sum();
+        let energy: f64 = wavelet.iter().map(|v| v * v).sum();
+        assert!(
+            (expected - energy).abs() < 1e-10,
+            "Energy not preserved. Expected {} but was {}",
+            expected,
+            energy
+        );
+    }
+
+    #[test]
+    fn in_place_matches_copy() {
+        let bank = daub4();
+        let expected = super::dwt(&bank, &DATA, BoundaryMode::Periodization, 4);
+
+        let mut data = DATA.to_vec();
+        super::dwt_in_place(&bank, &mut data, 4);
+        let delta = 1e-12;
+        for (pos, expected) in expected.iter().enumerate() {
+            assert!(
+                (expected - data[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                data[pos]
+            );
+        }
+
+        super::inverse_dwt_in_place(&bank, &mut data, DATA.len(), 4);
+        for (pos, expected) in DATA.iter().enumerate() {
+            assert!(
+                (expected - data[pos]).abs() < delta,
+                "Element at pos {} incorrect. Expected {} but was {}",
+                pos,
+                expected,
+                data[pos]
+            );
+        }
+    }
+
+    #[test]
+    fn decomposition_converts_to_transform() {
+        use crate::haar::{HaarDecomposition, HaarWavelet};
+        use crate::{MRDecomposition, WaveletTransform};
+
+        let expected = HaarWavelet::transform(&DATA);
+        let transform: HaarWavelet = HaarDecomposition::decompose(&DATA).into();
+
+        assert_eq!(
+            expected.coefficients(),
+            transform.coefficients(),
+            "Decomposition does not match the transform!"
+        );
+    }
+
+    #[test]
+    fn every_mode_inverts() {
+        use crate::biorthogonal::Bior2_4;
+        use crate::filter::Filter;
+
+        let modes = [
+            BoundaryMode::Zero,
+            BoundaryMode::Constant,
+            BoundaryMode::Symmetric,
+            BoundaryMode::Reflect,
+            BoundaryMode::Periodic,
+            BoundaryMode::Smooth,
+            BoundaryMode::Antisymmetric,
+        ];
+        for bank in [daub4(), Bior2_4::filter_bank()] {
+            for mode in modes {
+                for len in [5, 13, 16] {
+                    let data = &DATA[..len];
+                    let levels = super::max_levels(&bank, len, mode);
+                    let wavelet = super::dwt(&bank, data, mode, levels);
+                    let inverse = super::inverse_dwt(&bank, &wavelet, len, mode, levels);
+                    assert_eq!(len, inverse.len(), "{:?} changed the length!", mode);
+                    for (pos, expected) in data.iter().enumerate() {
+                        assert!(
+                            (expected - inverse[pos]).abs() < 1e-12,
+                            "{:?}: Element at pos {} incorrect. Expected {} but was {}",
+                            mode,
+                            pos,
+                            expected,
+                            inverse[pos]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_extensions_avoid_edge_differences() {
+        use crate::daubechies::Daub4Wavelet;
+
+        // a straight line has no differences at all with DAUB4, except where the extension
+        // doesn't continue it
+        let ramp: Vec<f64> = (0..12).map(|v| v as f64).collect();
+        let finest = |mode| {
+            let wavelet = Daub4Wavelet::transform_with(&ramp, mode);
+            let finest = (ramp.len() + 3) / 2;
+            let coefficients = wavelet.coefficients();
+            coefficients[coefficients.len() - finest..]
+                .iter()
+                .fold(0.0, |m: f64, d| m.max(d.abs()))
+        };
+
+        assert!(finest(BoundaryMode::Zero) > 1.0, "Zero padding should jump");
+        assert!(
+            finest(BoundaryMode::Smooth) < 1e-12,
+            "Smooth should be exact"
+        );
+        assert!(
+            finest(BoundaryMode::Symmetric) < finest(BoundaryMode::Zero) / 4.0,
+            "Symmetric should be much smoother than zero padding"
+        );
+    }
+
+    #[test]
+    fn transforms_any_length() {
+        use crate::biorthogonal::Bior3_3Wavelet;
+        use crate::daubechies::Daub6Wavelet;
+        use crate::WaveletTransform;
+
+        for len in [1, 2, 3, 5, 6, 7, 11, 13, 15] {
+            let data = &DATA[..len];
+            let copy = Daub6Wavelet::transform(data);
+            let mut in_place = Daub6Wavelet::transform_in_place(data.to_vec());
+            assert_eq!(
+                copy.coefficients().len(),
+                in_place.coefficients().len(),
+                "Incorrect number of coefficients in place"
+            );
+            for (expected, actual) in copy.coefficients().iter().zip(in_place.coefficients()) {
+                assert!(
+                    (expected - actual).abs() < 1e-12,
+                    "In place transform incorrect. Expected {} but was {}",
+                    expected,
+                    actual
+                );
+            }
+
+            let inverses = [
+                copy.invert(),
+                in_place.invert_in_place(),
+                Bior3_3Wavelet::transform(data).invert(),
+            ];
+            for inverse in inverses {
+                assert_eq!(len, inverse.len(), "Inverse has the wrong length!");
+                for (pos, expected) in data.iter().enumerate() {
+                    assert!(
+                        (expected - inverse[pos]).abs() < 1e-12,
+                        "Element at pos {} incorrect. Expected {} but was {}",
+                        pos,
+                        expected,
+                        inverse[pos]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fallible_transforms_report_errors() {
+        use crate::daubechies::{Daub4Decomposition, Daub4Wavelet};
+        use crate::error::WaveletError;
+        use crate::{MRDecomposition, WaveletTransform};
+
+        let mut data = DATA.to_vec();
+        data[5] = f64::NAN;
+        assert_eq!(
+            WaveletError::NonFiniteInput { index: 5 },
+            Daub4Wavelet::try_transform(&data).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::NonFiniteInput { index: 5 },
+            Daub4Decomposition::try_decompose(&data).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::LevelTooDeep {
+                requested: 3,
+                max: 2
+            },
+            Daub4Wavelet::try_transform_with(&DATA, BoundaryMode::Symmetric, 3).unwrap_err()
+        );
+        assert_eq!(
+            WaveletError::InvalidLength {
+                expected: 16,
+                actual: 15
+            },
+            Daub4Wavelet::try_from_coefficients(vec![0.0; 15], 16, BoundaryMode::Periodization, 4)
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn partial_transforms_invert() {
+        use crate::daubechies::{Daub4Decomposition, Daub4Wavelet};
+        use crate::WaveletTransform;
+
+        for mode in [BoundaryMode::Periodization, BoundaryMode::Symmetric] {
+            let wavelet = Daub4Wavelet::try_transform_with(&DATA[..13], mode, 2).unwrap();
+            assert_eq!(2, wavelet.levels(), "Incorrect number of levels");
+
+            let decomposition = Daub4Decomposition::try_decompose_with(&DATA[..13], mode, 2);
+            let converted: Daub4Wavelet = decomposition.unwrap().into();
+            assert_eq!(
+                wavelet.coefficients(),
+                converted.coefficients(),
+                "Decomposition does not match the transform!"
+            );
+
+            let rebuilt =
+                Daub4Wavelet::try_from_coefficients(wavelet.coefficients().to_vec(), 13, mode, 2)
+                    .unwrap();
+            let inverse = rebuilt.invert();
+            assert_eq!(13, inverse.len(), "Inverse has the wrong length!");
+            for (pos, expected) in DATA[..13].iter().enumerate() {
+                assert!(
+                    (expected - inverse[pos]).abs() < 1e-12,
+                    "Element at pos {} incorrect. Expected {} but was {}",
+                    pos,
+                    expected,
+                    inverse[pos]
+                );
+            }
+        }
+    }
+}